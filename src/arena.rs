@@ -1,15 +1,41 @@
-use crate::generation::{FixedGenerationalIndex, GenerationalIndex};
+use crate::error::{
+    CapacityOverflow, DisjointError, LookupError, RelocateError, RemovalUnsupported,
+};
+use crate::generation::{DisableRemoval, FixedGenerationalIndex, GenerationalIndex};
 use crate::index::{ArenaIndex, Index};
 use core::{
     cmp,
+    convert::TryFrom,
+    hash::{Hash, Hasher},
+    hint,
     iter::{self, FromIterator, FusedIterator},
-    mem, ops,
+    mem, ops, ptr,
 };
-use im::vector::{ConsumingIter, Iter as ImIter, IterMut as ImIterMut};
+use im::vector::{ConsumingIter, FocusMut, Iter as ImIter, IterMut as ImIterMut};
 use im::Vector;
+use num_traits::ToPrimitive;
+
+cfg_if! {
+    if #[cfg(feature = "std")] {
+        use std::vec::Vec;
+    } else {
+        use alloc::vec::Vec;
+    }
+}
 
+mod borrows;
+mod builder;
+mod codec;
+mod diff;
+mod fingerprint;
+mod frozen;
 pub mod rayon;
 
+pub use self::borrows::Borrows;
+pub use self::builder::{ArenaBuilder, FreePolicy, GrowthStrategy};
+pub use self::diff::{ArenaOp, OwnedArenaOp, SlotDelta};
+pub use self::frozen::FrozenArena;
+
 ///
 /// [See the module-level documentation for example usage and motivation.](./index.html)
 #[derive(Clone, Debug)]
@@ -19,28 +45,120 @@ pub struct Arena<T: Clone, I: Clone = usize, G: Clone = usize> {
     generation: G,
     len: usize,
     free_list_head: Option<I>,
+    max_len: Option<usize>,
+    free_policy: FreePolicy,
+    growth: GrowthStrategy,
+    /// The last slot of the free list, used by `FreePolicy::Fifo`. Whenever
+    /// this is set it is exact, so every change to the free list must keep it
+    /// up to date or clear it; `None` means the list is empty or its tail
+    /// has to be found by walking it.
+    free_list_tail: Option<I>,
+    no_grow: bool,
+    /// Set by `reserve_and_seal`; forbids growth like `no_grow` until the
+    /// arena is cleared or unsealed.
+    sealed: bool,
+    version: u64,
+    /// The number of elements ever removed, for `total_removals`.
+    removals: u64,
+    /// Set by `set_deferred_removal`.
+    deferred_removal: bool,
+    /// Slots freed while removal is deferred, which are left off the free
+    /// list until `commit_removals`.
+    pending_removals: Vec<I>,
 }
 
+/// A run of consecutive occupied slots, as yielded by `Arena::runs`.
+type Run<'a, T, I, G> = (ops::Range<usize>, Vec<(Index<T, I, G>, &'a T)>);
+
+/// An element's index before and after `Arena::rotate_generations`.
+type Remap<T, I, G> = (Index<T, I, G>, Index<T, I, G>);
+
+/// The two halves returned by `Arena::split_live_at`.
+type LiveSplit<'a, T, I, G> = (iter::Take<Iter<'a, T, I, G>>, iter::Skip<Iter<'a, T, I, G>>);
+
+/// Two consecutive elements, as yielded by `Arena::live_pairs`.
+type LivePair<'a, T, I, G> = ((Index<T, I, G>, &'a T), (Index<T, I, G>, &'a T));
+
+/// The element evicted by `Arena::insert_or_evict`, if any.
+type Evicted<T, I, G> = Option<(Index<T, I, G>, T)>;
+
 #[derive(Clone, Debug)]
-enum Entry<T, I = usize, G = u64> {
-    Free { next_free: Option<I> },
-    Occupied { generation: G, value: T },
+pub(crate) enum Entry<T, I = usize, G = u64> {
+    Free {
+        next_free: Option<I>,
+    },
+    Occupied {
+        generation: G,
+        value: T,
+    },
+    /// A slot retired by `remove_saturating`, which must never be reused.
+    Retired,
 }
 
-/// An index (and generation) into an `Arena`.
-///
-/// To get an `Index`, insert an element into an `Arena`, and the `Index` for
-/// that element will be returned.
-///
-/// # Examples
-///
-/// ```
-/// use generational_arena_im::StandardArena;
+/// The raw state of one slot of an arena, as reported by
+/// [`Arena::debug_entries`](struct.Arena.html#method.debug_entries).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EntryKind<I = usize, G = usize> {
+    /// The slot holds an element of this generation.
+    Occupied {
+        /// The generation of the element.
+        generation: G,
+    },
+    /// The slot is free.
+    Free {
+        /// The next slot of the free list.
+        next_free: Option<I>,
+    },
+    /// The slot was retired by
+    /// [`Arena::remove_saturating`](struct.Arena.html#method.remove_saturating)
+    /// and will never be reused.
+    Retired,
+}
+
+/// What the slot of an index currently holds, as reported by
+/// [`Arena::inspect`](struct.Arena.html#method.inspect).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SlotState<'a, T, G = usize> {
+    /// The index is live and refers to this element.
+    Live(&'a T),
+    /// The slot holds an element of a different generation, so the index is
+    /// stale.
+    Recycled {
+        /// The generation of the element now in the slot.
+        current_gen: G,
+        /// The element now in the slot.
+        value: &'a T,
+    },
+    /// The slot is free.
+    Free,
+    /// The slot is beyond the arena's capacity.
+    OutOfRange,
+}
+
+/// A cheap summary of an arena's state, as returned by
+/// [`Arena::stamp`](struct.Arena.html#method.stamp).
 ///
-/// let mut arena = StandardArena::new();
-/// let idx = arena.insert(123);
-/// assert_eq!(arena[idx], 123);
-/// ```
+/// Two stamps of the same arena compare equal exactly when no element was
+/// inserted or removed and the capacity did not change in between.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct StateStamp<G = usize> {
+    /// The number of elements, as with `Arena::len`.
+    pub len: usize,
+    /// The arena's generation, as with `Arena::generation`.
+    pub generation: G,
+    /// The number of slots, as with `Arena::capacity`.
+    pub capacity: usize,
+    /// The arena's version, as with `Arena::version`.
+    pub version: u64,
+}
+
+impl<T, I, G> Entry<T, I, G> {
+    /// Is this a free slot which may be reused? Occupied and retired slots
+    /// are not.
+    fn is_reusable(&self) -> bool {
+        matches!(self, Entry::Free { .. })
+    }
+}
 
 pub(crate) const DEFAULT_CAPACITY: usize = 4;
 
@@ -59,6 +177,27 @@ impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> Arena<T, I, G> {
         Arena::with_capacity(DEFAULT_CAPACITY)
     }
 
+    /// An arena with no slots and the default settings, which every
+    /// constructor starts from.
+    fn empty() -> Arena<T, I, G> {
+        Arena {
+            items: Vector::new(),
+            generation: G::first_generation(),
+            free_list_head: None,
+            len: 0,
+            max_len: None,
+            free_policy: FreePolicy::Lifo,
+            growth: GrowthStrategy::Double,
+            free_list_tail: None,
+            no_grow: false,
+            sealed: false,
+            version: 0,
+            removals: 0,
+            deferred_removal: false,
+            pending_removals: Vec::new(),
+        }
+    }
+
     /// Constructs a new, empty `Arena<T>` with the specified capacity.
     ///
     /// The `Arena<T>` will be able to hold `n` elements without further allocation.
@@ -80,18 +219,86 @@ impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> Arena<T, I, G> {
     /// ```
     pub fn with_capacity(n: usize) -> Arena<T, I, G> {
         let n = cmp::max(n, 1);
-        let mut arena = Arena {
-            items: Vector::new(),
-            generation: G::first_generation(),
-            free_list_head: None,
-            len: 0,
-        };
+        let mut arena = Arena::empty();
         arena.reserve(n);
         arena
     }
 
+    /// Constructs an `Arena` whose slots mirror `slots`: each `Some(value)`
+    /// becomes an occupied slot and each `None` a free slot, at the same
+    /// position.
+    ///
+    /// All elements are at the first generation, and the free slots are
+    /// reused in ascending order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::{StandardArena, StandardIndex};
+    ///
+    /// let arena = StandardArena::from_slots(vec![Some('a'), None, Some('c')]);
+    /// assert_eq!(arena.len(), 2);
+    /// assert_eq!(arena[StandardIndex::from_idx_first_gen(2)], 'c');
+    /// ```
+    pub fn from_slots<It: IntoIterator<Item = Option<T>>>(slots: It) -> Arena<T, I, G> {
+        let mut arena = Arena::empty();
+        let mut last_free: Option<usize> = None;
+        for (i, slot) in slots.into_iter().enumerate() {
+            match slot {
+                Some(value) => {
+                    arena.items.push_back(Entry::Occupied {
+                        generation: G::first_generation(),
+                        value,
+                    });
+                    arena.len += 1;
+                }
+                None => {
+                    arena.items.push_back(Entry::Free { next_free: None });
+                    let this = Some(I::from_idx(i));
+                    match last_free {
+                        None => arena.free_list_head = this,
+                        Some(prev) => arena.items[prev] = Entry::Free { next_free: this },
+                    }
+                    last_free = Some(i);
+                }
+            }
+        }
+        arena.free_list_tail = last_free.map(I::from_idx);
+        arena
+    }
+
+    /// Swap the contents of this arena with those of `other`, in O(1).
+    ///
+    /// Everything is exchanged, including capacity, the free list and any
+    /// configuration such as the limit set by
+    /// [`set_max_len`](#method.set_max_len), so each arena's indices keep
+    /// working on the arena that now holds their elements. Both arenas have
+    /// the same type, so no runtime check is needed for the swap to make
+    /// sense.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut front = StandardArena::new();
+    /// let mut back = StandardArena::new();
+    /// let a = front.insert("front");
+    /// let b = back.insert("back");
+    ///
+    /// front.swap_contents(&mut back);
+    /// assert_eq!(front[b], "back");
+    /// assert_eq!(back[a], "front");
+    /// ```
+    pub fn swap_contents(&mut self, other: &mut Self) {
+        mem::swap(self, other);
+    }
+
     /// Clear all the items inside the arena, but keep its allocation.
     ///
+    /// This also lifts any seal set by
+    /// [`reserve_and_seal`](#method.reserve_and_seal).
+    ///
     /// # Examples
     ///
     /// ```
@@ -109,7 +316,12 @@ impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> Arena<T, I, G> {
         let cap = self.items.len();
         self.items.clear();
         self.free_list_head = None;
+        self.free_list_tail = None;
+        self.pending_removals.clear();
+        self.removals += self.len as u64;
         self.len = 0;
+        self.version += 1;
+        self.sealed = false;
         self.reserve(cap);
     }
 
@@ -141,15 +353,22 @@ impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> Arena<T, I, G> {
     /// ```
     #[inline]
     pub fn try_insert(&mut self, value: T) -> Result<Index<T, I, G>, T> {
+        if self.is_at_max_len() {
+            return Err(value);
+        }
         match self.free_list_head {
             None => Err(value),
             Some(i) => {
                 let idx = i.to_idx();
                 match &self.items[idx] {
-                    Entry::Occupied { .. } => panic!("corrupt free list"),
+                    Entry::Occupied { .. } | Entry::Retired => panic!("corrupt free list"),
                     Entry::Free { next_free } => {
                         self.free_list_head = *next_free;
+                        if self.free_list_head.is_none() {
+                            self.free_list_tail = None;
+                        }
                         self.len += 1;
+                        self.version += 1;
                         self.items[idx] = Entry::Occupied {
                             generation: self.generation,
                             value,
@@ -165,6 +384,16 @@ impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> Arena<T, I, G> {
     ///
     /// The `value`'s associated index in the arena is returned.
     ///
+    /// # Panics
+    ///
+    /// Panics if the arena has reached the limit set by
+    /// [`set_max_len`](#method.set_max_len), or if it is full and either was
+    /// built with [`ArenaBuilder::no_grow`](struct.ArenaBuilder.html#method.no_grow),
+    /// is sealed by [`reserve_and_seal`](#method.reserve_and_seal), or its
+    /// index type cannot address any more slots. See
+    /// [`checked_insert`](#method.checked_insert) for a non-panicking
+    /// alternative.
+    ///
     /// # Examples
     ///
     /// ```
@@ -185,37 +414,149 @@ impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> Arena<T, I, G> {
 
     #[inline(never)]
     fn insert_slow_path(&mut self, value: T) -> Index<T, I, G> {
-        let len = self.items.len();
-        self.reserve(len);
+        assert!(!self.is_at_max_len(), "arena is at its maximum length");
+        assert!(self.may_grow(), "arena is full and may not grow");
+        self.grow_for_insert().expect("arena index space exhausted");
         self.try_insert(value)
             .map_err(|_| ())
             .expect("inserting will always succeed after reserving additional space")
     }
 
-    /// Is the element at index `i` in the arena?
+    /// Insert `value` unless an equal element is already in the arena,
+    /// returning the index of the existing element or of the new one.
     ///
-    /// Returns `true` if the element at `i` is in the arena, `false` otherwise.
+    /// This is handy for small interning tables, but it scans every element
+    /// to look for an equal one, so it takes time linear in the capacity. For
+    /// large sets, keep a separate map from values to indices instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`insert`](#method.insert) when
+    /// no equal element exists.
     ///
     /// # Examples
     ///
     /// ```
     /// use generational_arena_im::StandardArena;
     ///
-    /// let mut arena = StandardArena::new();
-    /// let idx = arena.insert(42);
+    /// let mut strings = StandardArena::new();
+    /// let a = strings.insert_unique("hello");
+    /// let b = strings.insert_unique("hello");
+    /// assert_eq!(a, b);
+    /// assert_eq!(strings.len(), 1);
+    /// ```
+    pub fn insert_unique(&mut self, value: T) -> Index<T, I, G>
+    where
+        T: PartialEq,
+    {
+        match self.find(|existing| *existing == value) {
+            Some((idx, _)) => idx,
+            None => self.insert(value),
+        }
+    }
+
+    /// Insert every element of `values` into consecutive slots at the end of
+    /// the arena, returning their indices in order.
+    ///
+    /// Free slots left by earlier removals are not reused, so the batch is
+    /// always laid out contiguously in the backing store.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the batch would take the arena beyond the limit set by
+    /// [`set_max_len`](#method.set_max_len), if the arena may not grow, or if
+    /// the new slots cannot be addressed by the arena's index type.
+    ///
+    /// # Examples
     ///
-    /// assert!(arena.contains(idx));
-    /// arena.remove(idx);
-    /// assert!(!arena.contains(idx));
     /// ```
-    pub fn contains(&self, i: Index<T, I, G>) -> bool {
-        self.get(i).is_some()
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::with_capacity(1);
+    /// let a = arena.insert(0);
+    /// arena.remove(a);
+    ///
+    /// let batch = arena.insert_contiguous(vec![1, 2, 3].into_iter());
+    /// let slots: Vec<_> = batch.iter().map(|idx| idx.to_idx()).collect();
+    /// assert_eq!(slots, vec![1, 2, 3]);
+    /// ```
+    pub fn insert_contiguous<V>(&mut self, values: V) -> Vec<Index<T, I, G>>
+    where
+        V: ExactSizeIterator<Item = T>,
+    {
+        self.check_contiguous_room(values.len());
+        values.map(|value| self.push_slot(value)).collect()
     }
 
-    /// Get a shared reference to the element at index `i` if it is in the
-    /// arena.
+    /// Check that `count` elements can be pushed after every existing slot,
+    /// panicking as `insert_contiguous` documents otherwise.
+    fn check_contiguous_room(&self, count: usize) {
+        if let Some(max_len) = self.max_len {
+            assert!(
+                self.len.saturating_add(count) <= max_len,
+                "arena is at its maximum length"
+            );
+        }
+        assert!(
+            count == 0 || self.may_grow(),
+            "arena is full and may not grow"
+        );
+        let start = self.items.len();
+        if count > 0 {
+            start
+                .checked_add(count - 1)
+                .and_then(I::try_from_idx)
+                .expect("arena index space exhausted");
+        }
+    }
+
+    /// Put `value` in a new slot after every existing slot.
+    fn push_slot(&mut self, value: T) -> Index<T, I, G> {
+        let slot = I::from_idx(self.items.len());
+        self.items.push_back(Entry::Occupied {
+            generation: self.generation,
+            value,
+        });
+        self.len += 1;
+        self.version += 1;
+        Index::new(slot, self.generation)
+    }
+
+    /// Insert `value` into a new slot after every existing slot, ignoring
+    /// any free slots.
     ///
-    /// If the element at index `i` is not in the arena, then `None` is returned.
+    /// Together with [`pop`](#method.pop) this lets the arena be used as a
+    /// stack whose elements keep stable indices.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as
+    /// [`insert_contiguous`](#method.insert_contiguous).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::with_capacity(4);
+    /// let idx = arena.push(1);
+    /// assert_eq!(idx.to_idx(), 4);
+    /// ```
+    pub fn push(&mut self, value: T) -> Index<T, I, G> {
+        self.check_contiguous_room(1);
+        self.push_slot(value)
+    }
+
+    /// Insert values generated by `f` until the arena holds `target_len`
+    /// elements.
+    ///
+    /// Unlike `Vec::resize_with`, this never removes elements: it does
+    /// nothing if the arena already holds `target_len` or more. Use
+    /// [`truncate_live`](#method.truncate_live) to shrink the element count.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`insert`](#method.insert).
     ///
     /// # Examples
     ///
@@ -223,52 +564,188 @@ impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> Arena<T, I, G> {
     /// use generational_arena_im::StandardArena;
     ///
     /// let mut arena = StandardArena::new();
-    /// let idx = arena.insert(42);
+    /// arena.insert(0);
     ///
-    /// assert_eq!(arena.get(idx), Some(&42));
-    /// arena.remove(idx);
-    /// assert!(arena.get(idx).is_none());
+    /// arena.resize_with(3, || 7);
+    /// assert_eq!(arena.len(), 3);
+    ///
+    /// arena.resize_with(1, || 7);
+    /// assert_eq!(arena.len(), 3);
     /// ```
-    pub fn get(&self, i: Index<T, I, G>) -> Option<&T> {
-        match self.items.get(i.index.to_idx()) {
-            Some(Entry::Occupied {
-                generation,
-                ref value,
-            }) if *generation == i.generation => Some(value),
-            _ => None,
+    pub fn resize_with<F>(&mut self, target_len: usize, mut f: F)
+    where
+        F: FnMut() -> T,
+    {
+        while self.len < target_len {
+            self.insert(f());
         }
     }
 
-    /// Get an exclusive reference to the element at index `i` if it is in the
-    /// arena.
+    /// Insert values produced by `f` into every free slot, without growing
+    /// the arena.
     ///
-    /// If the element at index `i` is not in the arena, then `None` is returned.
+    /// Afterwards `len() == capacity()`, unless the limit set by
+    /// [`set_max_len`](#method.set_max_len) stopped the filling early or some
+    /// slots were retired by exhausting their generations.
     ///
     /// # Examples
     ///
     /// ```
     /// use generational_arena_im::StandardArena;
     ///
-    /// let mut arena = StandardArena::new();
-    /// let idx = arena.insert(42);
+    /// let mut arena = StandardArena::with_capacity(4);
+    /// arena.insert(1);
     ///
-    /// *arena.get_mut(idx).unwrap() += 1;
-    /// assert_eq!(arena.remove(idx), Some(43));
-    /// assert!(arena.get_mut(idx).is_none());
+    /// arena.fill_with(|| 0);
+    /// assert_eq!(arena.len(), 4);
+    /// assert_eq!(arena.capacity(), 4);
     /// ```
-    pub fn get_mut(&mut self, i: Index<T, I, G>) -> Option<&mut T> {
-        match self.items.get_mut(i.index.to_idx()) {
-            Some(Entry::Occupied {
-                generation,
-                ref mut value,
-            }) if *generation == i.generation => Some(value),
-            _ => None,
+    pub fn fill_with<F>(&mut self, mut f: F)
+    where
+        F: FnMut() -> T,
+    {
+        while self.has_free() && !self.is_at_max_len() {
+            if self.try_insert(f()).is_err() {
+                break;
+            }
         }
     }
 
-    /// Get the length of this arena.
+    /// Insert `value` into the arena, allocating more capacity if necessary,
+    /// without panicking.
     ///
-    /// The length is the number of elements the arena holds.
+    /// This is the fallible counterpart of [`insert`](#method.insert): if the
+    /// arena has reached its maximum length, or is full and cannot grow any
+    /// further, `Err(value)` is returned to give ownership of `value` back to
+    /// the caller.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::NanoArena;
+    ///
+    /// // A `NanoArena` can address at most 256 slots.
+    /// let mut arena = NanoArena::new();
+    /// for i in 0..256 {
+    ///     assert!(arena.checked_insert(i).is_ok());
+    /// }
+    /// assert_eq!(arena.checked_insert(256), Err(256));
+    /// ```
+    pub fn checked_insert(&mut self, value: T) -> Result<Index<T, I, G>, T> {
+        match self.try_insert(value) {
+            Ok(i) => Ok(i),
+            Err(value) if self.is_at_max_len() || !self.may_grow() => Err(value),
+            Err(value) => match self.grow_for_insert() {
+                Ok(()) => self.try_insert(value),
+                Err(CapacityOverflow) => Err(value),
+            },
+        }
+    }
+
+    /// Insert `value` into the arena unless it has reached the limit set by
+    /// [`set_max_len`](#method.set_max_len), allocating more capacity if
+    /// necessary.
+    ///
+    /// This is the insertion to use for bounded pools: once the arena holds
+    /// its maximum number of elements, `Err(value)` is returned to give
+    /// ownership of `value` back to the caller. Otherwise it behaves exactly
+    /// like [`insert`](#method.insert).
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`insert`](#method.insert), other
+    /// than reaching the maximum length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut pool = StandardArena::with_capacity(1);
+    /// pool.set_max_len(2);
+    ///
+    /// assert!(pool.insert_within_max_len("a").is_ok());
+    /// assert!(pool.insert_within_max_len("b").is_ok());
+    /// assert_eq!(pool.insert_within_max_len("c"), Err("c"));
+    /// ```
+    pub fn insert_within_max_len(&mut self, value: T) -> Result<Index<T, I, G>, T> {
+        if self.is_at_max_len() {
+            return Err(value);
+        }
+        Ok(self.insert(value))
+    }
+
+    /// Insert `value` into the arena only if its slot is below `max_slot`,
+    /// allocating more capacity if necessary, without panicking.
+    ///
+    /// The slot is the one [`insert`](#method.insert) would use, and the
+    /// arena never grows past `max_slot` slots to provide it. If that slot is
+    /// not below `max_slot`, or `checked_insert` would fail, `Err(value)` is
+    /// returned to give ownership of `value` back to the caller.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::with_capacity(1);
+    /// for i in 0..3 {
+    ///     assert!(arena.insert_below(3, i).is_ok());
+    /// }
+    /// assert_eq!(arena.insert_below(3, 3), Err(3));
+    /// assert_eq!(arena.capacity(), 3);
+    /// ```
+    pub fn insert_below(&mut self, max_slot: usize, value: T) -> Result<Index<T, I, G>, T> {
+        if self.is_at_max_len() {
+            return Err(value);
+        }
+        if self.free_list_head.is_none() {
+            let room = max_slot.saturating_sub(self.items.len());
+            if room == 0 || !self.may_grow() {
+                return Err(value);
+            }
+            let additional = cmp::min(self.growth_amount(), room);
+            if self
+                .try_reserve(additional)
+                .or_else(|_| self.try_reserve(1))
+                .is_err()
+            {
+                return Err(value);
+            }
+        }
+        match self.free_list_head {
+            Some(i) if i.to_idx() < max_slot => self.try_insert(value),
+            _ => Err(value),
+        }
+    }
+
+    /// Limit the number of elements the arena may hold to `max_len`.
+    ///
+    /// Once the arena holds `max_len` elements,
+    /// [`insert_within_max_len`](#method.insert_within_max_len),
+    /// `try_insert` and `checked_insert` return the value back and `insert`
+    /// panics, regardless of how much capacity is allocated. Elements already
+    /// in the arena are kept even if there are more than `max_len` of them.
+    /// Remove the limit again with [`clear_max_len`](#method.clear_max_len).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::with_capacity(10);
+    /// arena.set_max_len(1);
+    ///
+    /// assert!(arena.try_insert(1).is_ok());
+    /// assert_eq!(arena.try_insert(2), Err(2));
+    /// assert_eq!(arena.checked_insert(2), Err(2));
+    /// ```
+    pub fn set_max_len(&mut self, max_len: usize) {
+        self.max_len = Some(max_len);
+    }
+
+    /// Remove the limit set by [`set_max_len`](#method.set_max_len), so the
+    /// arena may hold any number of elements again.
     ///
     /// # Examples
     ///
@@ -276,22 +753,2516 @@ impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> Arena<T, I, G> {
     /// use generational_arena_im::StandardArena;
     ///
     /// let mut arena = StandardArena::new();
-    /// assert_eq!(arena.len(), 0);
+    /// arena.set_max_len(0);
+    /// assert_eq!(arena.try_insert(1), Err(1));
     ///
-    /// let idx = arena.insert(42);
-    /// assert_eq!(arena.len(), 1);
+    /// arena.clear_max_len();
+    /// assert_eq!(arena.max_len(), None);
+    /// assert!(arena.try_insert(1).is_ok());
+    /// ```
+    pub fn clear_max_len(&mut self) {
+        self.max_len = None;
+    }
+
+    /// Get the maximum number of elements the arena may hold, if one was set
+    /// with [`set_max_len`](#method.set_max_len).
+    pub fn max_len(&self) -> Option<usize> {
+        self.max_len
+    }
+
+    /// Choose how much capacity is added when inserting into a full arena.
     ///
-    /// let _ = arena.insert(0);
-    /// assert_eq!(arena.len(), 2);
+    /// By default the capacity doubles, which keeps the amortized cost of
+    /// inserting low. Arenas that grow to a known size can trade more
+    /// frequent reallocation for less unused capacity.
+    ///
+    /// # Examples
     ///
-    /// assert_eq!(arena.remove(idx), Some(42));
-    /// assert_eq!(arena.len(), 1);
     /// ```
-    pub fn len(&self) -> usize {
-        self.len
+    /// use generational_arena_im::{GrowthStrategy, StandardArena};
+    ///
+    /// let mut arena = StandardArena::with_capacity(4);
+    /// arena.set_growth(GrowthStrategy::Exact);
+    /// for i in 0..5 {
+    ///     arena.insert(i);
+    /// }
+    /// assert_eq!(arena.capacity(), 5);
+    /// ```
+    pub fn set_growth(&mut self, growth: GrowthStrategy) {
+        self.growth = growth;
+    }
+
+    /// Get the strategy set with [`set_growth`](#method.set_growth).
+    pub fn growth(&self) -> GrowthStrategy {
+        self.growth
+    }
+
+    /// Grow the arena to a capacity of at least `capacity`, then forbid it
+    /// from growing any further.
+    ///
+    /// Once sealed, inserting into a full arena fails as if it had been built
+    /// with [`ArenaBuilder::no_grow`](struct.ArenaBuilder.html#method.no_grow):
+    /// `checked_insert` returns the value back and `insert` panics. Free slots
+    /// can still be filled. The seal is lifted by [`unseal`](#method.unseal)
+    /// or [`clear`](#method.clear).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::with_capacity(1);
+    /// arena.reserve_and_seal(2);
+    /// assert!(arena.checked_insert(1).is_ok());
+    /// assert!(arena.checked_insert(2).is_ok());
+    /// assert_eq!(arena.checked_insert(3), Err(3));
+    ///
+    /// arena.unseal();
+    /// assert!(arena.checked_insert(3).is_ok());
+    /// ```
+    pub fn reserve_and_seal(&mut self, capacity: usize) {
+        self.reserve(capacity.saturating_sub(self.items.len()));
+        self.sealed = true;
+    }
+
+    /// Allow an arena sealed by [`reserve_and_seal`](#method.reserve_and_seal)
+    /// to grow again.
+    pub fn unseal(&mut self) {
+        self.sealed = false;
+    }
+
+    fn may_grow(&self) -> bool {
+        !self.no_grow && !self.sealed
+    }
+
+    fn is_at_max_len(&self) -> bool {
+        self.max_len.is_some_and(|max_len| self.len >= max_len)
+    }
+
+    /// The number of slots the growth strategy adds to a full arena.
+    fn growth_amount(&self) -> usize {
+        let additional = match self.growth {
+            GrowthStrategy::Double => self.items.len(),
+            GrowthStrategy::Additive(n) => n,
+            GrowthStrategy::Exact => 1,
+        };
+        cmp::max(additional, 1)
+    }
+
+    /// Grow the arena as its growth strategy says, or by a single slot if
+    /// that would overflow the index type.
+    fn grow_for_insert(&mut self) -> Result<(), CapacityOverflow> {
+        let additional = self.growth_amount();
+        self.try_reserve(additional)
+            .or_else(|_| self.try_reserve(1))
+    }
+
+    /// Is the element at index `i` in the arena?
+    ///
+    /// Returns `true` if the element at `i` is in the arena, `false` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let idx = arena.insert(42);
+    ///
+    /// assert!(arena.contains(idx));
+    /// arena.remove(idx);
+    /// assert!(!arena.contains(idx));
+    /// ```
+    pub fn contains(&self, i: Index<T, I, G>) -> bool {
+        self.get(i).is_some()
+    }
+
+    /// Are all of the elements at `indices` in the arena?
+    ///
+    /// Returns `true` for an empty slice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let a = arena.insert(1);
+    /// let b = arena.insert(2);
+    ///
+    /// assert!(arena.all_live(&[a, b]));
+    /// arena.remove(b);
+    /// assert!(!arena.all_live(&[a, b]));
+    /// ```
+    pub fn all_live(&self, indices: &[Index<T, I, G>]) -> bool {
+        self.first_stale(indices).is_none()
+    }
+
+    /// Get the first index in `indices` whose element is not in the arena.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let a = arena.insert(1);
+    /// let b = arena.insert(2);
+    /// arena.remove(b);
+    ///
+    /// assert_eq!(arena.first_stale(&[a, b]), Some(b));
+    /// assert_eq!(arena.first_stale(&[a]), None);
+    /// ```
+    pub fn first_stale(&self, indices: &[Index<T, I, G>]) -> Option<Index<T, I, G>> {
+        indices.iter().copied().find(|&i| !self.contains(i))
+    }
+
+    /// Is `value` held by any element of the arena?
+    ///
+    /// This scans every element, so it takes time linear in the capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// arena.insert("a");
+    ///
+    /// assert!(arena.contains_value(&"a"));
+    /// assert!(!arena.contains_value(&"b"));
+    /// ```
+    pub fn contains_value(&self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|(_, v)| v == value)
+    }
+
+    /// Find the first element in slot order for which `predicate` returns
+    /// `true`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// arena.insert(1);
+    /// let two = arena.insert(2);
+    /// arena.insert(4);
+    ///
+    /// assert_eq!(arena.find(|v| v % 2 == 0), Some((two, &2)));
+    /// assert_eq!(arena.find(|v| *v > 10), None);
+    /// ```
+    pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<(Index<T, I, G>, &T)> {
+        self.iter().find(|(_, value)| predicate(value))
+    }
+
+    /// Apply `f` to the elements in slot order, returning the first
+    /// non-`None` result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// arena.insert("apple");
+    /// let b = arena.insert("banana");
+    ///
+    /// let found = arena.find_map(|idx, name| if name.starts_with('b') { Some(idx) } else { None });
+    /// assert_eq!(found, Some(b));
+    /// ```
+    pub fn find_map<R>(&self, mut f: impl FnMut(Index<T, I, G>, &T) -> Option<R>) -> Option<R> {
+        self.iter().find_map(|(idx, value)| f(idx, value))
+    }
+
+    /// Collect the indices of the elements for which `predicate` returns
+    /// `true`, in slot order.
+    ///
+    /// Indices are `Copy` and do not borrow the arena, so the result can be
+    /// used as a worklist for mutating the arena afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// for i in 0..5 {
+    ///     arena.insert(i);
+    /// }
+    ///
+    /// for idx in arena.indices_where(|v| v % 2 == 0) {
+    ///     arena[idx] *= 10;
+    /// }
+    /// let values: Vec<_> = arena.iter().map(|(_, v)| *v).collect();
+    /// assert_eq!(values, vec![0, 1, 20, 3, 40]);
+    /// ```
+    pub fn indices_where(&self, predicate: impl Fn(&T) -> bool) -> Vec<Index<T, I, G>> {
+        self.iter()
+            .filter(|(_, value)| predicate(value))
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Get the index of the element that `value` refers to by scanning every
+    /// slot, which takes time linear in the capacity and may also recognize
+    /// a reference into a clone of this arena.
+    ///
+    /// Elements are identified by address rather than by value, so this
+    /// recovers the index of a reference obtained from the arena even when
+    /// other elements compare equal to it. The backing store is not
+    /// contiguous, so there is no way to compute the slot from the address;
+    /// each slot's address is compared in turn. Clones of an arena share
+    /// storage until one of them is modified, so a reference obtained from a
+    /// clone can point at an element this arena shares, and the index of
+    /// this arena's copy of it is returned. Only pass references obtained
+    /// from this arena if that matters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// arena.insert(7);
+    /// let idx = arena.insert(7);
+    ///
+    /// let value = arena.get(idx).unwrap();
+    /// assert_eq!(arena.index_of(value), Some(idx));
+    /// assert_eq!(arena.index_of(&7), None);
+    /// ```
+    pub fn index_of(&self, value: &T) -> Option<Index<T, I, G>> {
+        self.iter()
+            .find(|(_, v)| ptr::eq(*v, value))
+            .map(|(idx, _)| idx)
+    }
+
+    /// Get the element in the lowest occupied slot after the slot of `i`.
+    ///
+    /// Only the slot of `i` is used, so `i` need not refer to an element
+    /// still in the arena. Together with [`prev_before`](#method.prev_before)
+    /// this allows walking the arena in slot order one element at a time
+    /// without holding on to an iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let a = arena.insert('a');
+    /// let b = arena.insert('b');
+    /// let c = arena.insert('c');
+    /// arena.remove(b);
+    ///
+    /// assert_eq!(arena.next_after(a), Some((c, &'c')));
+    /// assert_eq!(arena.next_after(c), None);
+    /// ```
+    pub fn next_after(&self, i: Index<T, I, G>) -> Option<(Index<T, I, G>, &T)> {
+        let start = i.index.to_idx().saturating_add(1);
+        if start >= self.items.len() {
+            return None;
+        }
+        self.items
+            .focus()
+            .narrow(start..)
+            .into_iter()
+            .zip(start..)
+            .find_map(|(entry, slot)| Self::occupied_at(entry, slot))
+    }
+
+    /// Get the element in the highest occupied slot before the slot of `i`.
+    ///
+    /// The counterpart of [`next_after`](#method.next_after).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let a = arena.insert('a');
+    /// let b = arena.insert('b');
+    /// let c = arena.insert('c');
+    /// arena.remove(b);
+    ///
+    /// assert_eq!(arena.prev_before(c), Some((a, &'a')));
+    /// assert_eq!(arena.prev_before(a), None);
+    /// ```
+    pub fn prev_before(&self, i: Index<T, I, G>) -> Option<(Index<T, I, G>, &T)> {
+        let end = cmp::min(i.index.to_idx(), self.items.len());
+        if end == 0 {
+            return None;
+        }
+        self.items
+            .focus()
+            .narrow(..end)
+            .into_iter()
+            .zip(0..end)
+            .rev()
+            .find_map(|(entry, slot)| Self::occupied_at(entry, slot))
+    }
+
+    fn occupied_at(entry: &Entry<T, I, G>, slot: usize) -> Option<(Index<T, I, G>, &T)> {
+        match entry {
+            Entry::Occupied { generation, value } => {
+                Some((Index::new(I::from_idx(slot), *generation), value))
+            }
+            _ => None,
+        }
+    }
+
+    /// Get a shared reference to the element at index `i` if it is in the
+    /// arena.
+    ///
+    /// If the element at index `i` is not in the arena, then `None` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let idx = arena.insert(42);
+    ///
+    /// assert_eq!(arena.get(idx), Some(&42));
+    /// arena.remove(idx);
+    /// assert!(arena.get(idx).is_none());
+    /// ```
+    pub fn get(&self, i: Index<T, I, G>) -> Option<&T> {
+        match self.items.get(i.index.to_idx()) {
+            Some(Entry::Occupied {
+                generation,
+                ref value,
+            }) if *generation == i.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Get a clone of the element at index `i` if it is in the arena.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let idx = arena.insert(String::from("hello"));
+    ///
+    /// assert_eq!(arena.get_cloned(idx), Some(String::from("hello")));
+    /// arena.remove(idx);
+    /// assert_eq!(arena.get_cloned(idx), None);
+    /// ```
+    pub fn get_cloned(&self, i: Index<T, I, G>) -> Option<T> {
+        self.get(i).cloned()
+    }
+
+    /// Get a clone of the element at index `i`, or `default` if it is not in
+    /// the arena.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let idx = arena.insert(1);
+    ///
+    /// assert_eq!(arena.get_or(idx, 0), 1);
+    /// arena.remove(idx);
+    /// assert_eq!(arena.get_or(idx, 0), 0);
+    /// ```
+    pub fn get_or(&self, i: Index<T, I, G>, default: T) -> T {
+        self.get(i).cloned().unwrap_or(default)
+    }
+
+    /// Get an exclusive reference to the element at index `i` if it is in the
+    /// arena.
+    ///
+    /// If the element at index `i` is not in the arena, then `None` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let idx = arena.insert(42);
+    ///
+    /// *arena.get_mut(idx).unwrap() += 1;
+    /// assert_eq!(arena.remove(idx), Some(43));
+    /// assert!(arena.get_mut(idx).is_none());
+    /// ```
+    pub fn get_mut(&mut self, i: Index<T, I, G>) -> Option<&mut T> {
+        match self.items.get_mut(i.index.to_idx()) {
+            Some(Entry::Occupied {
+                generation,
+                ref mut value,
+            }) if *generation == i.generation => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Get exclusive references to the elements at indices `i1` and `i2`,
+    /// reporting why each one could not be found.
+    ///
+    /// Two indices for the same slot cannot both be live, so at most one of
+    /// them resolves and the other is `Err(LookupError::Stale)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i1` and `i2` are the same live index, since that would
+    /// create two exclusive references to one element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::{LookupError, StandardArena};
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let a = arena.insert(1);
+    /// let b = arena.insert(2);
+    ///
+    /// if let (Ok(a), Ok(b)) = arena.get2_mut_checked(a, b) {
+    ///     std::mem::swap(a, b);
+    /// }
+    /// assert_eq!(arena[a], 2);
+    ///
+    /// arena.remove(b);
+    /// let (a, b) = arena.get2_mut_checked(a, b);
+    /// assert_eq!(a, Ok(&mut 2));
+    /// assert_eq!(b, Err(LookupError::Stale));
+    /// ```
+    pub fn get2_mut_checked(
+        &mut self,
+        i1: Index<T, I, G>,
+        i2: Index<T, I, G>,
+    ) -> (Result<&mut T, LookupError>, Result<&mut T, LookupError>) {
+        match (self.lookup_slot(i1), self.lookup_slot(i2)) {
+            (Ok(s1), Ok(s2)) => {
+                assert!(
+                    s1 != s2,
+                    "get2_mut_checked called with two identical indices"
+                );
+                let (lo, hi) = (cmp::min(s1, s2), cmp::max(s1, s2));
+                let (left, right) = self.items.focus_mut().split_at(hi);
+                let lo_value = Self::occupied_mut(left.narrow(lo..lo + 1), lo).next();
+                let hi_value = Self::occupied_mut(right.narrow(0..1), hi).next();
+                let (lo_value, hi_value) = match (lo_value, hi_value) {
+                    (Some((_, lo_value)), Some((_, hi_value))) => (lo_value, hi_value),
+                    _ => unreachable!(),
+                };
+                if s1 < s2 {
+                    (Ok(lo_value), Ok(hi_value))
+                } else {
+                    (Ok(hi_value), Ok(lo_value))
+                }
+            }
+            (Ok(_), Err(e2)) => (Ok(self.get_mut(i1).unwrap()), Err(e2)),
+            (Err(e1), Ok(_)) => (Err(e1), Ok(self.get_mut(i2).unwrap())),
+            (Err(e1), Err(e2)) => (Err(e1), Err(e2)),
+        }
+    }
+
+    /// Get exclusive references to the elements of a keyed batch of indices
+    /// at once, pairing each reference with its key.
+    ///
+    /// The results are in the order of `handles`. Every index must resolve,
+    /// so a stale or out of bounds index fails the whole batch with
+    /// `DisjointError::Lookup`, and no two indices may refer to the same
+    /// element, or the batch fails with `DisjointError::Overlapping`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::{DisjointError, StandardArena};
+    /// use std::collections::HashMap;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let mut ids = HashMap::new();
+    /// ids.insert("left", arena.insert(1));
+    /// ids.insert("right", arena.insert(2));
+    ///
+    /// for (_, value) in arena.get_disjoint_mut_from(ids.clone()).unwrap() {
+    ///     *value *= 10;
+    /// }
+    /// assert_eq!(arena[ids["left"]], 10);
+    /// assert_eq!(arena[ids["right"]], 20);
+    ///
+    /// let twice = vec![("a", ids["left"]), ("b", ids["left"])];
+    /// assert_eq!(
+    ///     arena.get_disjoint_mut_from(twice).unwrap_err(),
+    ///     DisjointError::Overlapping
+    /// );
+    /// ```
+    pub fn get_disjoint_mut_from<K>(
+        &mut self,
+        handles: impl IntoIterator<Item = (K, Index<T, I, G>)>,
+    ) -> Result<Vec<(K, &mut T)>, DisjointError> {
+        let mut requests = Vec::new();
+        for (position, (key, i)) in handles.into_iter().enumerate() {
+            let slot = self.lookup_slot(i).map_err(DisjointError::Lookup)?;
+            requests.push((slot, position, key));
+        }
+        requests.sort_by_key(|(slot, _, _)| *slot);
+        if requests.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+            return Err(DisjointError::Overlapping);
+        }
+
+        let mut found = Vec::with_capacity(requests.len());
+        let mut rest = self.items.focus_mut();
+        let mut start = 0;
+        for (slot, position, key) in requests {
+            let (_, right) = rest.split_at(slot - start);
+            let (entry, tail) = right.split_at(1);
+            let value = match Self::occupied_mut(entry, slot).next() {
+                Some((_, value)) => value,
+                None => unreachable!(),
+            };
+            found.push((position, key, value));
+            rest = tail;
+            start = slot + 1;
+        }
+        found.sort_by_key(|(position, _, _)| *position);
+        Ok(found
+            .into_iter()
+            .map(|(_, key, value)| (key, value))
+            .collect())
+    }
+
+    /// Get exclusive references to the elements at a fixed number of
+    /// indices at once, or `None` unless every index resolves.
+    ///
+    /// This is all or nothing: a single stale or out of bounds index, or two
+    /// indices referring to the same element, yields `None` rather than any
+    /// partial set of references. The references are in the order of
+    /// `indices`. Use
+    /// [`get_disjoint_mut_from`](#method.get_disjoint_mut_from) to find out
+    /// why a batch failed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let a = arena.insert(1);
+    /// let b = arena.insert(2);
+    ///
+    /// if let Some([x, y]) = arena.try_get_disjoint_mut([a, b]) {
+    ///     std::mem::swap(x, y);
+    /// }
+    /// assert_eq!((arena[a], arena[b]), (2, 1));
+    ///
+    /// assert!(arena.try_get_disjoint_mut([a, a]).is_none());
+    /// arena.remove(b);
+    /// assert!(arena.try_get_disjoint_mut([a, b]).is_none());
+    /// ```
+    pub fn try_get_disjoint_mut<const N: usize>(
+        &mut self,
+        indices: [Index<T, I, G>; N],
+    ) -> Option<[&mut T; N]> {
+        let found = self
+            .get_disjoint_mut_from(indices.iter().map(|i| ((), *i)))
+            .ok()?;
+        <[&mut T; N]>::try_from(
+            found
+                .into_iter()
+                .map(|(_, value)| value)
+                .collect::<Vec<_>>(),
+        )
+        .ok()
+    }
+
+    fn lookup_slot(&self, i: Index<T, I, G>) -> Result<usize, LookupError> {
+        let slot = i.index.to_idx();
+        match self.items.get(slot) {
+            None => Err(LookupError::OutOfBounds),
+            Some(Entry::Occupied { generation, .. }) if *generation == i.generation => Ok(slot),
+            Some(_) => Err(LookupError::Stale),
+        }
+    }
+
+    /// Get the length of this arena.
+    ///
+    /// The length is the number of elements the arena holds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// assert_eq!(arena.len(), 0);
+    ///
+    /// let idx = arena.insert(42);
+    /// assert_eq!(arena.len(), 1);
+    ///
+    /// let _ = arena.insert(0);
+    /// assert_eq!(arena.len(), 2);
+    ///
+    /// assert_eq!(arena.remove(idx), Some(42));
+    /// assert_eq!(arena.len(), 1);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the arena contains no elements
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// assert!(arena.is_empty());
+    ///
+    /// let idx = arena.insert(42);
+    /// assert!(!arena.is_empty());
+    ///
+    /// assert_eq!(arena.remove(idx), Some(42));
+    /// assert!(arena.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the capacity of this arena.
+    ///
+    /// The capacity is the maximum number of elements the arena can hold
+    /// without further allocation, including however many it currently
+    /// contains.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::with_capacity(10);
+    /// assert_eq!(arena.capacity(), 10);
+    ///
+    /// // `try_insert` does not allocate new capacity.
+    /// for i in 0..10 {
+    ///     assert!(arena.try_insert(1).is_ok());
+    ///     assert_eq!(arena.capacity(), 10);
+    /// }
+    ///
+    /// // But `insert` will if the arena is already at capacity.
+    /// arena.insert(0);
+    /// assert!(arena.capacity() > 10);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Is there a free slot the next insertion can use without growing the
+    /// arena?
+    ///
+    /// A limit set by [`set_max_len`](#method.set_max_len) can still make
+    /// the insertion fail.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::with_capacity(1);
+    /// assert!(arena.has_free());
+    /// let idx = arena.insert(1);
+    /// assert!(!arena.has_free());
+    /// arena.remove(idx);
+    /// assert!(arena.has_free());
+    /// ```
+    pub fn has_free(&self) -> bool {
+        self.free_list_head.is_some()
+    }
+
+    /// Get the slot the next insertion will use, or `None` if the arena has
+    /// to grow to insert another element.
+    pub fn first_free(&self) -> Option<I> {
+        self.free_list_head
+    }
+
+    /// Get the number of free slots in the arena, `capacity() - len()`.
+    ///
+    /// This is how many elements `try_insert` can accept before the arena
+    /// has to grow, except that slots retired by
+    /// [`remove_saturating`](#method.remove_saturating) are counted as free
+    /// even though they are never reused, and slots of deferred removals are
+    /// counted as free before they are committed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::with_capacity(4);
+    /// arena.insert(1);
+    /// assert_eq!(arena.free_count(), 3);
+    /// ```
+    pub fn free_count(&self) -> usize {
+        self.capacity() - self.len
+    }
+
+    /// Get the fraction of the arena's capacity that is not occupied.
+    ///
+    /// This is `(capacity - len) / capacity`: close to `0.0` for a dense
+    /// arena and close to `1.0` for one that is mostly free slots. An arena
+    /// with no capacity has a fragmentation of `0.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::with_capacity(4);
+    /// let a = arena.insert(1);
+    /// arena.insert(2);
+    /// assert_eq!(arena.fragmentation(), 0.5);
+    ///
+    /// arena.remove(a);
+    /// assert_eq!(arena.fragmentation(), 0.75);
+    /// ```
+    pub fn fragmentation(&self) -> f64 {
+        let capacity = self.capacity();
+        if capacity == 0 {
+            return 0.0;
+        }
+        (capacity - self.len) as f64 / capacity as f64
+    }
+
+    /// Allocate space for `additional_capacity` more elements in the arena.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this causes the capacity to overflow, or if the new slots
+    /// cannot be addressed by the arena's index type. See
+    /// [`try_reserve`](#method.try_reserve) for a non-panicking alternative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::with_capacity(10);
+    /// arena.reserve(5);
+    /// assert_eq!(arena.capacity(), 15);
+    /// # let _: StandardArena<usize> = arena;
+    /// ```
+    pub fn reserve(&mut self, additional_capacity: usize) {
+        if additional_capacity == 0 {
+            return;
+        }
+        let start = self.items.len();
+        let end = self.items.len() + additional_capacity;
+        let old_head = self.free_list_head;
+        for i in start..end {
+            let entry = if i == end - 1 {
+                Entry::Free {
+                    next_free: old_head,
+                }
+            } else {
+                Entry::Free {
+                    next_free: Some(I::from_idx(i + 1)),
+                }
+            };
+            self.items.push_back(entry);
+        }
+        self.free_list_head = Some(I::from_idx(start));
+        if old_head.is_none() {
+            self.free_list_tail = Some(I::from_idx(end - 1));
+        }
+    }
+
+    /// Allocate space for `additional_capacity` more elements in the arena,
+    /// returning the slots that were added.
+    ///
+    /// The new slots are free, and are the next ones used by `insert`.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`reserve`](#method.reserve).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::<u32>::with_capacity(4);
+    /// assert_eq!(arena.reserve_range(3), 4..7);
+    /// assert_eq!(arena.capacity(), 7);
+    /// ```
+    pub fn reserve_range(&mut self, additional_capacity: usize) -> ops::Range<usize> {
+        let start = self.items.len();
+        self.reserve(additional_capacity);
+        start..self.items.len()
+    }
+
+    /// Allocate space for `additional_capacity` more elements in the arena,
+    /// then read and write each new slot once.
+    ///
+    /// This is for latency-sensitive code, such as a real-time thread, which
+    /// must not page-fault on its first use of a slot. Reserving already
+    /// initializes every new slot, but touching them again afterwards makes
+    /// sure the memory they live in is backed and exclusively owned by this
+    /// arena before the latency-sensitive work starts.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`reserve`](#method.reserve).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::<u32>::with_capacity(4);
+    /// arena.reserve_and_touch(60);
+    /// assert_eq!(arena.capacity(), 64);
+    /// ```
+    pub fn reserve_and_touch(&mut self, additional_capacity: usize) {
+        let slots = self.reserve_range(additional_capacity);
+        for entry in self.items.iter_mut().skip(slots.start) {
+            if let Entry::Free { next_free } = entry {
+                *next_free = hint::black_box(*next_free);
+            }
+        }
+    }
+
+    /// Allocate space for the peak number of elements of a workload which
+    /// inserts many elements but keeps only a fraction `density` of them,
+    /// expecting `expected_live` to survive.
+    ///
+    /// This ensures the arena can hold `expected_live / density` more
+    /// elements than it does now without growing, so that building up to
+    /// the peak before pruning reallocates at most once. It does not change
+    /// how the arena grows afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `density` is not in `(0, 1]`, or under the same conditions
+    /// as [`reserve`](#method.reserve).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::<u32>::new();
+    /// arena.reserve_sparse(10, 0.5);
+    /// assert_eq!(arena.capacity(), 20);
+    /// ```
+    pub fn reserve_sparse(&mut self, expected_live: usize, density: f64) {
+        assert!(density > 0.0 && density <= 1.0, "density must be in (0, 1]");
+        let peak = expected_live as f64 / density;
+        let mut peak_len = peak as usize;
+        if (peak_len as f64) < peak {
+            peak_len += 1;
+        }
+        let target = self.len.saturating_add(peak_len);
+        if target > self.items.len() {
+            self.reserve(target - self.items.len());
+        }
+    }
+
+    /// Try to allocate space for `additional_capacity` more elements in the
+    /// arena.
+    ///
+    /// This is the fallible counterpart of [`reserve`](#method.reserve): if
+    /// the new capacity would overflow, or the new slots could not be
+    /// addressed by the arena's index type, the arena is left unchanged and
+    /// `Err(CapacityOverflow)` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::NanoArena;
+    ///
+    /// let mut arena = NanoArena::<usize>::with_capacity(10);
+    /// assert!(arena.try_reserve(246).is_ok());
+    /// assert!(arena.try_reserve(1).is_err());
+    /// assert_eq!(arena.capacity(), 256);
+    /// ```
+    pub fn try_reserve(&mut self, additional_capacity: usize) -> Result<(), CapacityOverflow> {
+        let end = self
+            .items
+            .len()
+            .checked_add(additional_capacity)
+            .ok_or(CapacityOverflow)?;
+        if additional_capacity > 0 && I::try_from_idx(end - 1).is_none() {
+            return Err(CapacityOverflow);
+        }
+        self.reserve(additional_capacity);
+        Ok(())
+    }
+
+    /// Grow the arena to every slot its index type can address, so that it
+    /// never has to grow again.
+    ///
+    /// Returns `Err(CapacityOverflow)`, leaving the arena unchanged, if that
+    /// many slots could never fit in memory, as is the case for `usize`
+    /// indices. Otherwise the allocation is attempted, and failing to
+    /// allocate aborts as with any other growth.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::NanoArena;
+    ///
+    /// let mut arena = NanoArena::<u32>::new();
+    /// arena.reserve_to_index_max().unwrap();
+    /// assert_eq!(arena.capacity(), 256);
+    /// ```
+    pub fn reserve_to_index_max(&mut self) -> Result<(), CapacityOverflow> {
+        // Binary search for the number of addressable slots.
+        let (mut lo, mut hi) = (0, usize::MAX);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2 + 1;
+            if I::try_from_idx(mid - 1).is_some() {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+        let max_bytes = isize::MAX as usize;
+        match lo.checked_mul(mem::size_of::<Entry<T, I, G>>()) {
+            Some(bytes) if bytes <= max_bytes => {}
+            _ => return Err(CapacityOverflow),
+        }
+        self.try_reserve(lo.saturating_sub(self.items.len()))
+    }
+
+    /// Shrink the capacity of the arena as much as possible, but no lower
+    /// than `min_capacity`.
+    ///
+    /// Only trailing free slots are released, so occupied elements never move
+    /// and every outstanding index stays valid. The capacity never drops
+    /// below `len()`, nor below the highest occupied slot. If `min_capacity`
+    /// is at least the current capacity, this is a no-op.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::with_capacity(10);
+    /// let idx = arena.insert(42);
+    ///
+    /// arena.shrink_to(4);
+    /// assert_eq!(arena.capacity(), 4);
+    /// assert_eq!(arena[idx], 42);
+    /// ```
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        self.link_pending_removals();
+        let occupied_end = self
+            .items
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, entry)| !entry.is_reusable())
+            .map_or(0, |(slot, _)| slot + 1);
+        let new_capacity = cmp::max(cmp::max(min_capacity, self.len), occupied_end);
+        if new_capacity >= self.items.len() {
+            return;
+        }
+        self.unlink_free_slots_from(new_capacity);
+        self.items.truncate(new_capacity);
+    }
+
+    /// Report what the slot of index `i` currently holds, whether or not `i`
+    /// is still live.
+    ///
+    /// This is meant for diagnosing use of stale indices; use
+    /// [`get`](#method.get) for ordinary lookups.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::{SlotState, StandardArena};
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let old = arena.insert("old");
+    /// assert_eq!(arena.inspect(old), SlotState::Live(&"old"));
+    ///
+    /// arena.remove(old);
+    /// assert_eq!(arena.inspect(old), SlotState::Free);
+    ///
+    /// let new = arena.insert("new");
+    /// assert_eq!(
+    ///     arena.inspect(old),
+    ///     SlotState::Recycled { current_gen: new.gen(), value: &"new" }
+    /// );
+    /// ```
+    pub fn inspect(&self, i: Index<T, I, G>) -> SlotState<'_, T, G> {
+        match self.items.get(i.index.to_idx()) {
+            None => SlotState::OutOfRange,
+            Some(Entry::Free { .. }) | Some(Entry::Retired) => SlotState::Free,
+            Some(Entry::Occupied { generation, value }) if *generation == i.generation => {
+                SlotState::Live(value)
+            }
+            Some(Entry::Occupied { generation, value }) => SlotState::Recycled {
+                current_gen: *generation,
+                value,
+            },
+        }
+    }
+
+    /// Get how many generations behind the element now in its slot the
+    /// index `i` is.
+    ///
+    /// Returns `None` if `i` is live, its slot is free or out of range, or
+    /// its generation is not older than the slot's. The arena's generation
+    /// advances on every removal, not just removals from this slot, so the
+    /// difference counts the removals made between `i` being issued and its
+    /// slot being reused.
+    ///
+    /// For wrapping generation types, generations which may be on opposite
+    /// sides of a wrap are unordered, so `None` is returned for them. A
+    /// generation that has wrapped all the way around is indistinguishable
+    /// from a newer one, so the difference is only meaningful while it is
+    /// small compared to the generation's range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let old = arena.insert("old");
+    /// assert_eq!(arena.staleness(old), None);
+    ///
+    /// arena.remove(old);
+    /// arena.insert("new");
+    /// assert_eq!(arena.staleness(old), Some(1));
+    /// ```
+    pub fn staleness(&self, i: Index<T, I, G>) -> Option<u64>
+    where
+        G: ToPrimitive,
+    {
+        match self.inspect(i) {
+            SlotState::Recycled { current_gen, .. } => {
+                match i.generation.generation_partial_cmp(&current_gen) {
+                    Some(cmp::Ordering::Less) => {
+                        current_gen.to_u64()?.checked_sub(i.generation.to_u64()?)
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Iterate over the raw state of every slot, free or occupied, in slot
+    /// order.
+    ///
+    /// This exposes the physical layout of the arena, including the free
+    /// list, and is intended for debugging.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::{EntryKind, StandardArena};
+    ///
+    /// let mut arena = StandardArena::with_capacity(2);
+    /// let a = arena.insert(1);
+    /// let b = arena.insert(2);
+    /// arena.remove(a);
+    ///
+    /// let entries: Vec<_> = arena.debug_entries().collect();
+    /// assert_eq!(
+    ///     entries,
+    ///     vec![
+    ///         (0, EntryKind::Free { next_free: None }),
+    ///         (1, EntryKind::Occupied { generation: b.gen() }),
+    ///     ]
+    /// );
+    /// ```
+    pub fn debug_entries(&self) -> impl Iterator<Item = (usize, EntryKind<I, G>)> + '_ {
+        self.items.iter().enumerate().map(|(slot, entry)| {
+            let kind = match entry {
+                Entry::Occupied { generation, .. } => EntryKind::Occupied {
+                    generation: *generation,
+                },
+                Entry::Free { next_free } => EntryKind::Free {
+                    next_free: *next_free,
+                },
+                Entry::Retired => EntryKind::Retired,
+            };
+            (slot, kind)
+        })
+    }
+
+    /// Rebuild the free list and element count from the slots themselves.
+    ///
+    /// This is a recovery tool for arenas whose bookkeeping has been
+    /// corrupted, for example a free list that is cyclic, points out of
+    /// range or at an occupied or retired slot, or misses some free slots.
+    /// If the arena is consistent it is left unchanged. Otherwise the free
+    /// list is relinked in ascending slot order and the element count is
+    /// recomputed. Slots retired by
+    /// [`remove_saturating`](#method.remove_saturating) stay retired.
+    ///
+    /// Returns the number of inconsistencies that were fixed: one for a wrong
+    /// element count, one for a broken link, and one for every free slot
+    /// that was not reachable from the free list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let idx = arena.insert(1);
+    /// arena.remove(idx);
+    /// assert_eq!(arena.repair_free_list(), 0);
+    /// ```
+    pub fn repair_free_list(&mut self) -> usize {
+        self.link_pending_removals();
+        let capacity = self.items.len();
+        let mut fixed = 0;
+
+        let occupied = self
+            .items
+            .iter()
+            .filter(|entry| matches!(entry, Entry::Occupied { .. }))
+            .count();
+        if occupied != self.len {
+            self.len = occupied;
+            fixed += 1;
+        }
+
+        let mut seen = Vec::new();
+        seen.resize(capacity, false);
+        let mut reachable = 0;
+        let mut cur = self.free_list_head;
+        while let Some(i) = cur {
+            let idx = i.to_idx();
+            if idx >= capacity || seen[idx] || !self.items[idx].is_reusable() {
+                fixed += 1;
+                break;
+            }
+            seen[idx] = true;
+            reachable += 1;
+            cur = match self.items[idx] {
+                Entry::Free { next_free } => next_free,
+                Entry::Occupied { .. } | Entry::Retired => unreachable!(),
+            };
+        }
+        let reusable = self
+            .items
+            .iter()
+            .filter(|entry| entry.is_reusable())
+            .count();
+        if reachable == reusable && cur.is_none() {
+            return fixed;
+        }
+        fixed += reusable - reachable;
+
+        let mut head: Option<I> = None;
+        for slot in (0..capacity).rev() {
+            if self.items[slot].is_reusable() {
+                if head.is_none() {
+                    self.free_list_tail = Some(I::from_idx(slot));
+                }
+                self.items[slot] = Entry::Free { next_free: head };
+                head = Some(I::from_idx(slot));
+            }
+        }
+        self.free_list_head = head;
+        if head.is_none() {
+            self.free_list_tail = None;
+        }
+        fixed
+    }
+
+    /// Remove the element at index `i` from the arena, for any generation
+    /// type.
+    ///
+    /// This behaves like [`remove`](#method.remove), but is available on
+    /// every arena so that code generic over the generation type can call
+    /// it. Arenas whose generation type does not support removal, such as
+    /// slabs, return `Err(RemovalUnsupported)` and are left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::{RemovalUnsupported, StandardArena, StandardSlab};
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let idx = arena.insert(42);
+    /// assert_eq!(arena.try_remove(idx), Ok(Some(42)));
+    /// assert_eq!(arena.try_remove(idx), Ok(None));
+    ///
+    /// let mut slab = StandardSlab::new();
+    /// let idx = slab.insert(42);
+    /// assert_eq!(slab.try_remove(idx), Err(RemovalUnsupported));
+    /// ```
+    pub fn try_remove(&mut self, i: Index<T, I, G>) -> Result<Option<T>, RemovalUnsupported> {
+        // Advance a copy only to find out whether removal is supported, so
+        // that an unsupported removal leaves the arena untouched.
+        let mut generation = self.generation;
+        generation.increment_for_removal()?;
+        if !self.contains(i) {
+            return Ok(None);
+        }
+        let slot = i.index.to_idx();
+        match mem::replace(&mut self.items[slot], Entry::Free { next_free: None }) {
+            Entry::Occupied { value, .. } => {
+                self.finish_removal(i.index);
+                Ok(Some(value))
+            }
+            Entry::Free { .. } | Entry::Retired => unreachable!(),
+        }
+    }
+
+    /// Is every slot from `start` onwards free?
+    fn all_free_from(&self, start: usize) -> bool {
+        start >= self.items.len()
+            || self
+                .items
+                .focus()
+                .narrow(start..)
+                .into_iter()
+                .all(|entry| matches!(entry, Entry::Free { .. }))
+    }
+
+    /// Update the bookkeeping for the element just taken out of `slot`.
+    fn finish_removal(&mut self, slot: I) {
+        self.free_removed_slot(slot);
+        self.len -= 1;
+        self.version += 1;
+        self.removals += 1;
+    }
+
+    /// Record that `slot` has just been emptied by a removal, either freeing
+    /// it now or deferring that to `commit_removals`.
+    fn free_removed_slot(&mut self, slot: I) {
+        if self.deferred_removal {
+            self.pending_removals.push(slot);
+        } else if self.generation.increment_for_removal().is_ok() {
+            self.link_free(slot);
+        } else {
+            // Reusing the slot without a new generation would let the
+            // removed element's indices match the next element put there.
+            self.items[slot.to_idx()] = Entry::Retired;
+        }
+    }
+
+    /// Return the slots of deferred removals to the free list, advancing the
+    /// generation so that the removed elements' indices stay stale. If the
+    /// generation cannot advance, the slots are retired instead. Returns the
+    /// number of slots returned.
+    fn link_pending_removals(&mut self) -> usize {
+        if self.pending_removals.is_empty() {
+            return 0;
+        }
+        let advanced = self.generation.increment_for_removal().is_ok();
+        let mut pending = mem::take(&mut self.pending_removals);
+        pending.sort_by_key(|slot| slot.to_idx());
+        pending.dedup_by_key(|slot| slot.to_idx());
+        let mut linked = 0;
+        for slot in pending {
+            let idx = slot.to_idx();
+            if idx < self.items.len() && self.items[idx].is_reusable() {
+                if advanced {
+                    self.link_free(slot);
+                    linked += 1;
+                } else {
+                    // As in `free_removed_slot`, a slot cannot be reused
+                    // without a new generation.
+                    self.items[idx] = Entry::Retired;
+                }
+            }
+        }
+        linked
+    }
+
+    /// Add the newly freed slot `slot` to the free list, at the front or the
+    /// back depending on the arena's free policy.
+    fn link_free(&mut self, slot: I) {
+        match self.free_policy {
+            FreePolicy::Lifo => {
+                if self.free_list_head.is_none() {
+                    self.free_list_tail = Some(slot);
+                }
+                self.items[slot.to_idx()] = Entry::Free {
+                    next_free: self.free_list_head,
+                };
+                self.free_list_head = Some(slot);
+            }
+            FreePolicy::Fifo => {
+                let tail = self.find_free_list_tail();
+                self.items[slot.to_idx()] = Entry::Free { next_free: None };
+                match tail {
+                    None => self.free_list_head = Some(slot),
+                    Some(tail) => {
+                        self.items[tail] = Entry::Free {
+                            next_free: Some(slot),
+                        }
+                    }
+                }
+                self.free_list_tail = Some(slot);
+            }
+        }
+    }
+
+    /// Find the last slot of the free list, using `free_list_tail` if it is
+    /// known and walking the list otherwise.
+    fn find_free_list_tail(&self) -> Option<usize> {
+        let mut cur = self.free_list_head?.to_idx();
+        if let Some(tail) = self.free_list_tail {
+            return Some(tail.to_idx());
+        }
+        loop {
+            match &self.items[cur] {
+                Entry::Free {
+                    next_free: Some(next),
+                } => cur = next.to_idx(),
+                Entry::Free { next_free: None } => return Some(cur),
+                Entry::Occupied { .. } | Entry::Retired => panic!("corrupt free list"),
+            }
+        }
+    }
+
+    /// Unlink the free slot `slot` from the free list.
+    fn unlink_free_slot(&mut self, slot: usize) {
+        let mut prev: Option<usize> = None;
+        let mut cur = self.free_list_head;
+        while let Some(i) = cur {
+            let idx = i.to_idx();
+            let next = match &self.items[idx] {
+                Entry::Free { next_free } => *next_free,
+                Entry::Occupied { .. } | Entry::Retired => panic!("corrupt free list"),
+            };
+            if idx == slot {
+                match prev {
+                    None => self.free_list_head = next,
+                    Some(p) => self.items[p] = Entry::Free { next_free: next },
+                }
+                if next.is_none() {
+                    self.free_list_tail = prev.map(I::from_idx);
+                }
+                return;
+            }
+            prev = Some(idx);
+            cur = next;
+        }
+    }
+
+    /// Unlink every slot at or beyond `end` from the free list, keeping the
+    /// order of the remaining free slots.
+    fn unlink_free_slots_from(&mut self, end: usize) {
+        let mut prev: Option<usize> = None;
+        let mut cur = self.free_list_head;
+        while let Some(i) = cur {
+            let idx = i.to_idx();
+            let next = match &self.items[idx] {
+                Entry::Free { next_free } => *next_free,
+                Entry::Occupied { .. } | Entry::Retired => panic!("corrupt free list"),
+            };
+            if idx >= end {
+                match prev {
+                    None => self.free_list_head = next,
+                    Some(p) => self.items[p] = Entry::Free { next_free: next },
+                }
+            } else {
+                prev = Some(idx);
+            }
+            cur = next;
+        }
+        self.free_list_tail = prev.map(I::from_idx);
+    }
+
+    /// Iterate over shared references to the elements in this arena.
+    ///
+    /// Yields pairs of `(Index<T>, &T)` items.
+    ///
+    /// Order of iteration is not defined.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// for i in 0..10 {
+    ///     arena.insert(i * i);
+    /// }
+    ///
+    /// for (idx, value) in arena.iter() {
+    ///     println!("{} is at index {:?}", value, idx);
+    /// }
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T, I, G> {
+        Iter {
+            len: self.len,
+            inner: self.items.iter().enumerate(),
+        }
+    }
+
+    /// Iterate over shared references to the elements in this arena, in
+    /// strictly descending slot order.
+    ///
+    /// This is the same as `iter().rev()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// arena.insert(1);
+    /// arena.insert(2);
+    ///
+    /// let values: Vec<_> = arena.iter_rev().map(|(_, v)| *v).collect();
+    /// assert_eq!(values, vec![2, 1]);
+    /// ```
+    pub fn iter_rev(&self) -> iter::Rev<Iter<'_, T, I, G>> {
+        self.iter().rev()
+    }
+
+    /// Iterate over the elements in this arena along with their slots.
+    ///
+    /// Yields `(slot, Index<T>, &T)` items in slot order, where `slot` is the
+    /// element's position in the backing store and equal to
+    /// `index.to_idx()`. This suits code that keys other arrays by slot while
+    /// handing out indices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let a = arena.insert('a');
+    /// let b = arena.insert('b');
+    /// arena.remove(a);
+    ///
+    /// let physical: Vec<_> = arena.iter_physical().collect();
+    /// assert_eq!(physical, vec![(1, b, &'b')]);
+    /// ```
+    pub fn iter_physical(&self) -> impl Iterator<Item = (usize, Index<T, I, G>, &T)> + '_ {
+        self.items
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, entry)| match entry {
+                Entry::Occupied { generation, value } => {
+                    Some((slot, Index::new(I::from_idx(slot), *generation), value))
+                }
+                Entry::Free { .. } | Entry::Retired => None,
+            })
+    }
+
+    /// Iterate over the elements in this arena together with the entry of
+    /// `meta` at each element's slot.
+    ///
+    /// Yields `(Index<T>, &T, &M)` items in slot order, for metadata kept in
+    /// a separate array indexed by slot.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `meta` is shorter than the arena's capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::with_capacity(2);
+    /// let a = arena.insert('a');
+    /// let b = arena.insert('b');
+    /// arena.remove(a);
+    ///
+    /// let meta = [10, 20];
+    /// let paired: Vec<_> = arena.iter_with_slice(&meta).collect();
+    /// assert_eq!(paired, vec![(b, &'b', &20)]);
+    /// ```
+    pub fn iter_with_slice<'a, M>(
+        &'a self,
+        meta: &'a [M],
+    ) -> impl Iterator<Item = (Index<T, I, G>, &'a T, &'a M)> + 'a {
+        assert!(
+            meta.len() >= self.capacity(),
+            "slice is shorter than the arena's capacity"
+        );
+        self.items
+            .iter()
+            .zip(meta)
+            .enumerate()
+            .filter_map(|(slot, (entry, m))| match entry {
+                Entry::Occupied { generation, value } => {
+                    Some((Index::new(I::from_idx(slot), *generation), value, m))
+                }
+                Entry::Free { .. } | Entry::Retired => None,
+            })
+    }
+
+    /// Iterate over the maximal runs of consecutive occupied slots.
+    ///
+    /// Yields the range of slots of each run along with its elements, in slot
+    /// order. Consecutive runs are separated by at least one free slot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::with_capacity(4);
+    /// let indices: Vec<_> = (0..4).map(|i| arena.insert(i)).collect();
+    /// arena.remove(indices[1]);
+    ///
+    /// let ranges: Vec<_> = arena.runs().map(|(slots, _)| slots).collect();
+    /// assert_eq!(ranges, vec![0..1, 2..4]);
+    /// ```
+    pub fn runs(&self) -> impl Iterator<Item = Run<'_, T, I, G>> + '_ {
+        let mut elements = self.iter_physical().peekable();
+        iter::from_fn(move || {
+            let (start, index, value) = elements.next()?;
+            let mut run = Vec::new();
+            run.push((index, value));
+            let mut end = start + 1;
+            while let Some((_, index, value)) = elements.next_if(|&(slot, _, _)| slot == end) {
+                run.push((index, value));
+                end += 1;
+            }
+            Some((start..end, run))
+        })
+    }
+
+    /// Get the generation that newly inserted elements are given.
+    ///
+    /// Record this to later find the elements inserted since with
+    /// [`iter_since`](#method.iter_since).
+    pub fn generation(&self) -> G {
+        self.generation
+    }
+
+    /// Get a counter that changes whenever an element is inserted into or
+    /// removed from the arena, or indices are invalidated by
+    /// [`rotate_generations`](#method.rotate_generations).
+    ///
+    /// Between two calls returning the same version, every index resolves to
+    /// the same slot as before, so the result of a lookup can be cached
+    /// alongside the version and reused until the version changes. Mutating
+    /// elements in place does not change the version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let idx = arena.insert(1);
+    /// let version = arena.version();
+    ///
+    /// assert_eq!(arena.get(idx), Some(&1));
+    /// assert_eq!(arena.version(), version);
+    ///
+    /// arena.remove(idx);
+    /// assert_ne!(arena.version(), version);
+    /// ```
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Take a [`StateStamp`](struct.StateStamp.html) of this arena's length,
+    /// generation, capacity and version.
+    ///
+    /// Storing and comparing stamps is a cheap way to check whether an arena
+    /// changed, without keeping a clone of it around. Like the
+    /// [`version`](#method.version), the stamp does not change when elements
+    /// are mutated in place.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let stamp = arena.stamp();
+    /// assert_eq!(arena.stamp(), stamp);
+    ///
+    /// arena.insert(1);
+    /// assert_ne!(arena.stamp(), stamp);
+    /// ```
+    pub fn stamp(&self) -> StateStamp<G> {
+        StateStamp {
+            len: self.len,
+            generation: self.generation,
+            capacity: self.items.len(),
+            version: self.version,
+        }
+    }
+
+    /// Get the number of elements removed from the arena since it was
+    /// created.
+    ///
+    /// Every way of removing elements counts, including
+    /// [`clear`](#method.clear), [`drain`](#method.drain) and
+    /// [`retain`](#method.retain). Unlike the arena's generation, this
+    /// counter does not wrap for wrapping generation types, so it can be
+    /// used to measure churn.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let idx = arena.insert(1);
+    /// arena.remove(idx);
+    /// arena.remove(idx);
+    ///
+    /// assert_eq!(arena.total_removals(), 1);
+    /// ```
+    pub fn total_removals(&self) -> u64 {
+        self.removals
+    }
+
+    /// Get the number of bits needed to store the generation of any element
+    /// in the arena.
+    ///
+    /// This lets a serializer pick the narrowest integer type that can hold
+    /// every live generation. Returns 0 if the arena is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// arena.insert(1);
+    /// assert_eq!(arena.min_generation_bits(), 1);
+    /// ```
+    pub fn min_generation_bits(&self) -> u32
+    where
+        G: ToPrimitive,
+    {
+        self.iter()
+            .map(|(idx, _)| {
+                let generation = idx.generation.to_u64().unwrap_or(u64::MAX);
+                64 - generation.leading_zeros()
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Iterate over the elements whose generation is `since` or newer.
+    ///
+    /// Elements take the arena's current [`generation`](#method.generation)
+    /// when inserted, so passing a recorded generation yields the elements
+    /// inserted after it was recorded. This is approximate: the generation
+    /// only advances when an element is removed, so elements inserted before
+    /// the recording but after the last removal are yielded too.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// arena.insert("before");
+    /// let removed = arena.insert("removed");
+    /// arena.remove(removed);
+    ///
+    /// let since = arena.generation();
+    /// arena.insert("after");
+    ///
+    /// let new: Vec<_> = arena.iter_since(since).map(|(_, v)| *v).collect();
+    /// assert_eq!(new, vec!["after"]);
+    /// ```
+    pub fn iter_since(&self, since: G) -> impl Iterator<Item = (Index<T, I, G>, &T)> + '_ {
+        self.iter().filter(move |(idx, _)| {
+            matches!(
+                idx.generation.generation_partial_cmp(&since),
+                Some(cmp::Ordering::Greater) | Some(cmp::Ordering::Equal)
+            )
+        })
+    }
+
+    /// Split every element into two columns, in slot order.
+    ///
+    /// `f` maps each element to a pair, whose halves are pushed onto the
+    /// first and second returned vectors respectively, so both have `len()`
+    /// entries and the `n`th entries of both come from the same element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut points = StandardArena::new();
+    /// points.insert((1.0, 2.0));
+    /// points.insert((3.0, 4.0));
+    ///
+    /// let (xs, ys) = points.to_columns(|&(x, y)| (x, y));
+    /// assert_eq!(xs, vec![1.0, 3.0]);
+    /// assert_eq!(ys, vec![2.0, 4.0]);
+    /// ```
+    pub fn to_columns<K, V>(&self, f: impl Fn(&T) -> (K, V)) -> (Vec<K>, Vec<V>) {
+        let mut keys = Vec::with_capacity(self.len);
+        let mut values = Vec::with_capacity(self.len);
+        for (_, value) in self.iter() {
+            let (k, v) = f(value);
+            keys.push(k);
+            values.push(v);
+        }
+        (keys, values)
+    }
+
+    /// Construct a new arena holding clones of the elements for which
+    /// `predicate(index, &value)` returns `true`, leaving this arena as it is.
+    ///
+    /// The clones are packed into the lowest slots of the new arena in slot
+    /// order, so indices into this arena do not carry over. The new arena
+    /// has the same settings as this one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// for i in 0..6 {
+    ///     arena.insert(i);
+    /// }
+    ///
+    /// let evens = arena.clone_filtered(|_, v| v % 2 == 0);
+    /// let values: Vec<_> = evens.iter().map(|(_, v)| *v).collect();
+    /// assert_eq!(values, vec![0, 2, 4]);
+    /// assert_eq!(arena.len(), 6);
+    /// ```
+    pub fn clone_filtered(&self, predicate: impl Fn(Index<T, I, G>, &T) -> bool) -> Arena<T, I, G> {
+        let values: Vec<T> = self
+            .iter()
+            .filter(|&(index, value)| predicate(index, value))
+            .map(|(_, value)| value.clone())
+            .collect();
+        let mut arena = Arena::with_capacity(values.len());
+        arena.max_len = self.max_len;
+        arena.free_policy = self.free_policy;
+        arena.growth = self.growth;
+        arena.no_grow = self.no_grow;
+        for value in values {
+            arena.insert(value);
+        }
+        arena
+    }
+
+    /// Replace every element with the result of applying `f` to it.
+    ///
+    /// Each result stays in its element's slot with its generation, so every
+    /// index remains valid, and free slots are untouched. Unlike mutating
+    /// through [`iter_mut`](#method.iter_mut), `f` takes the elements by
+    /// value. If `f` panics, the element it was given is removed from the
+    /// arena.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let idx = arena.insert(String::from("  padded "));
+    ///
+    /// arena.map_in_place(|s| s.trim().to_owned());
+    /// assert_eq!(arena[idx], "padded");
+    /// ```
+    pub fn map_in_place(&mut self, mut f: impl FnMut(T) -> T) {
+        for i in 0..self.items.len() {
+            if !matches!(self.items[i], Entry::Occupied { .. }) {
+                continue;
+            }
+            let entry = mem::replace(&mut self.items[i], Entry::Free { next_free: None });
+            let (generation, value) = match entry {
+                Entry::Occupied { generation, value } => (generation, value),
+                Entry::Free { .. } | Entry::Retired => unreachable!(),
+            };
+            // As in `retain_map`, a panic in `f` loses the element.
+            let guard = RemoveOnUnwind {
+                arena: &mut *self,
+                slot: I::from_idx(i),
+            };
+            let value = f(value);
+            mem::forget(guard);
+            self.items[i] = Entry::Occupied { generation, value };
+        }
+    }
+
+    /// Iterate over exclusive references to the elements in this arena.
+    ///
+    /// Yields pairs of `(Index<T>, &mut T)` items.
+    ///
+    /// Order of iteration is not defined.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// for i in 0..10 {
+    ///     arena.insert(i * i);
+    /// }
+    ///
+    /// for (_idx, value) in arena.iter_mut() {
+    ///     *value += 5;
+    /// }
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, I, G> {
+        IterMut {
+            len: self.len,
+            inner: self.items.iter_mut().enumerate(),
+        }
+    }
+
+    /// Split the elements of this arena into at most `parts` iterators over
+    /// exclusive references, each covering a disjoint range of slots.
+    ///
+    /// The ranges are chosen so that the iterators yield nearly the same
+    /// number of elements. The iterators can be moved to other threads, which
+    /// makes this a building block for parallelism without rayon. Fewer than
+    /// `parts` iterators are returned if the arena holds fewer elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// for i in 0..10 {
+    ///     arena.insert(i);
+    /// }
+    ///
+    /// let parts = arena.split_live_mut(3);
+    /// let lens: Vec<_> = parts.into_iter().map(|part| part.count()).collect();
+    /// assert_eq!(lens, vec![3, 3, 4]);
+    /// ```
+    pub fn split_live_mut(
+        &mut self,
+        parts: usize,
+    ) -> Vec<impl Iterator<Item = (Index<T, I, G>, &mut T)> + '_> {
+        let parts = cmp::min(parts, self.len);
+        if parts == 0 {
+            return Vec::new();
+        }
+
+        // The slot of the first element of every part but the first.
+        let mut boundaries = Vec::with_capacity(parts - 1);
+        let mut rank = 0;
+        for (slot, entry) in self.items.iter().enumerate() {
+            if let Entry::Occupied { .. } = entry {
+                let part = boundaries.len() + 1;
+                if part < parts && rank == part * self.len / parts {
+                    boundaries.push(slot);
+                }
+                rank += 1;
+            }
+        }
+
+        let mut iters = Vec::with_capacity(parts);
+        let mut rest = self.items.focus_mut();
+        let mut start = 0;
+        for boundary in boundaries {
+            let (part, right) = rest.split_at(boundary - start);
+            iters.push(Self::occupied_mut(part, start));
+            rest = right;
+            start = boundary;
+        }
+        iters.push(Self::occupied_mut(rest, start));
+        iters
+    }
+
+    fn occupied_mut(
+        focus: FocusMut<'_, Entry<T, I, G>>,
+        start: usize,
+    ) -> impl Iterator<Item = (Index<T, I, G>, &mut T)> + '_ {
+        focus
+            .into_iter()
+            .enumerate()
+            .filter_map(move |(offset, entry)| match entry {
+                Entry::Occupied { generation, value } => {
+                    Some((Index::new(I::from_idx(start + offset), *generation), value))
+                }
+                Entry::Free { .. } | Entry::Retired => None,
+            })
+    }
+
+    /// Iterate over elements of the arena and remove them.
+    ///
+    /// Yields pairs of `(Index<T>, T)` items.
+    ///
+    /// Order of iteration is not defined.
+    ///
+    /// Note: All elements are removed even if the iterator is only partially consumed or not consumed at all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let idx_1 = arena.insert("hello");
+    /// let idx_2 = arena.insert("world");
+    ///
+    /// assert!(arena.get(idx_1).is_some());
+    /// assert!(arena.get(idx_2).is_some());
+    /// for (idx, value) in arena.drain() {
+    ///     assert!((idx == idx_1 && value == "hello") || (idx == idx_2 && value == "world"));
+    /// }
+    /// assert!(arena.get(idx_1).is_none());
+    /// assert!(arena.get(idx_2).is_none());
+    /// ```
+    pub fn drain(&mut self) -> Drain<T, I, G> {
+        let old_items = mem::take(&mut self.items);
+        let cap = old_items.len();
+        self.pending_removals.clear();
+        self.removals += self.len as u64;
+        self.len = 0;
+        self.version += 1;
+        self.free_list_head = None;
+        self.free_list_tail = None;
+        self.reserve(cap);
+        Drain {
+            inner: old_items.into_iter().enumerate(),
+        }
+    }
+
+    /// Consume the arena, yielding its elements along with their indices.
+    ///
+    /// Yields pairs of `(Index<T>, T)` items in slot order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let idx = arena.insert("hello");
+    ///
+    /// let pairs: Vec<_> = arena.into_iter_indexed().collect();
+    /// assert_eq!(pairs, vec![(idx, "hello")]);
+    /// ```
+    pub fn into_iter_indexed(self) -> impl Iterator<Item = (Index<T, I, G>, T)> {
+        Drain {
+            inner: self.items.into_iter().enumerate(),
+        }
+    }
+
+    /// Iterate over the elements of this arena in batches of up to `n`.
+    ///
+    /// Yields `Vec`s of `(Index<T>, &T)` pairs in slot order. Every batch
+    /// holds exactly `n` elements except possibly the last one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// for i in 0..5 {
+    ///     arena.insert(i);
+    /// }
+    ///
+    /// let sizes: Vec<_> = arena.chunks(2).map(|chunk| chunk.len()).collect();
+    /// assert_eq!(sizes, vec![2, 2, 1]);
+    /// ```
+    pub fn chunks(&self, n: usize) -> impl Iterator<Item = Vec<(Index<T, I, G>, &T)>> + '_ {
+        assert!(n != 0, "chunk size must be non-zero");
+        let mut inner = self.iter();
+        iter::from_fn(move || {
+            let chunk: Vec<_> = inner.by_ref().take(n).collect();
+            if chunk.is_empty() {
+                None
+            } else {
+                Some(chunk)
+            }
+        })
+    }
+
+    /// Split the elements of this arena, in slot order, into the first `n`
+    /// and the rest.
+    ///
+    /// This is a sequential analogue of the balanced parallel split, for
+    /// divide-and-conquer algorithms. If the arena holds fewer than `n`
+    /// elements, the second iterator is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// for i in 0..5 {
+    ///     arena.insert(i);
+    /// }
+    ///
+    /// let (front, back) = arena.split_live_at(2);
+    /// assert_eq!(front.map(|(_, v)| *v).collect::<Vec<_>>(), vec![0, 1]);
+    /// assert_eq!(back.map(|(_, v)| *v).collect::<Vec<_>>(), vec![2, 3, 4]);
+    /// ```
+    pub fn split_live_at(&self, n: usize) -> LiveSplit<'_, T, I, G> {
+        (self.iter().take(n), self.iter().skip(n))
+    }
+
+    /// Iterate over each pair of consecutive elements in slot order.
+    ///
+    /// Free slots are skipped, so an arena with `n` elements yields `n - 1`
+    /// pairs, or none if it has fewer than two elements. This is useful for
+    /// linking each element to the next one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::with_capacity(4);
+    /// let indices: Vec<_> = (0..4).map(|i| arena.insert(i)).collect();
+    /// arena.remove(indices[1]);
+    ///
+    /// let pairs: Vec<_> = arena.live_pairs().map(|((_, a), (_, b))| (*a, *b)).collect();
+    /// assert_eq!(pairs, vec![(0, 2), (2, 3)]);
+    /// ```
+    pub fn live_pairs(&self) -> impl Iterator<Item = LivePair<'_, T, I, G>> + '_ {
+        let mut elements = self.iter();
+        let mut previous = elements.next();
+        iter::from_fn(move || {
+            let current = elements.next()?;
+            Some((previous.replace(current)?, current))
+        })
+    }
+}
+
+impl<T: Clone, I: ArenaIndex, G: GenerationalIndex> Arena<T, I, G> {
+    /// Remove the element at index `i` from the arena.
+    ///
+    /// If the element at index `i` is still in the arena, then it is
+    /// returned. If it is not in the arena, then `None` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let idx = arena.insert(42);
+    ///
+    /// assert_eq!(arena.remove(idx), Some(42));
+    /// assert_eq!(arena.remove(idx), None);
+    /// ```
+    pub fn remove(&mut self, i: Index<T, I, G>) -> Option<T> {
+        if i.index.to_idx() >= self.items.len() {
+            return None;
+        }
+
+        let entry = mem::replace(
+            &mut self.items[i.index.to_idx()],
+            Entry::Free { next_free: None },
+        );
+        match entry {
+            Entry::Occupied { generation, value } => {
+                if generation == i.generation {
+                    self.free_removed_slot(i.index);
+                    self.len -= 1;
+                    self.version += 1;
+                    self.removals += 1;
+                    Some(value)
+                } else {
+                    self.items[i.index.to_idx()] = Entry::Occupied { generation, value };
+                    None
+                }
+            }
+            e => {
+                self.items[i.index.to_idx()] = e;
+                None
+            }
+        }
+    }
+
+    /// Remove the element at index `i` from the arena without ever
+    /// overflowing the arena's generation counter.
+    ///
+    /// This behaves like [`remove`](#method.remove), except when advancing
+    /// the generation would overflow `G`. In that case the element is still
+    /// removed and returned, but its slot is retired: it is not returned to
+    /// the free list, so no later insertion can reuse it and resurrect `i`,
+    /// and the generation is left unchanged. Wrapping generation types never
+    /// overflow, so for them this is the same as `remove`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let idx = arena.insert(42);
+    ///
+    /// assert_eq!(arena.remove_saturating(idx), Some(42));
+    /// assert_eq!(arena.remove_saturating(idx), None);
+    /// ```
+    pub fn remove_saturating(&mut self, i: Index<T, I, G>) -> Option<T> {
+        if !self.contains(i) {
+            return None;
+        }
+        let mut next = self.generation;
+        if next.checked_increment_generation() {
+            return self.remove(i);
+        }
+        match mem::replace(&mut self.items[i.index.to_idx()], Entry::Retired) {
+            Entry::Occupied { value, .. } => {
+                self.len -= 1;
+                self.version += 1;
+                self.removals += 1;
+                Some(value)
+            }
+            Entry::Free { .. } | Entry::Retired => unreachable!(),
+        }
+    }
+
+    /// Remove the element at index `i`, then release the trailing free slots
+    /// if it was the last element in slot order.
+    ///
+    /// This keeps an arena that is used like a stack, pushing and popping at
+    /// the end, from holding on to capacity it no longer needs. Other indices
+    /// stay valid, since only free slots are released. Removing an element
+    /// that has other elements after it behaves exactly like
+    /// [`remove`](#method.remove).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::with_capacity(2);
+    /// let a = arena.insert(1);
+    /// let b = arena.insert(2);
+    ///
+    /// assert_eq!(arena.remove_and_trim(b), Some(2));
+    /// assert_eq!(arena.capacity(), 1);
+    /// assert_eq!(arena[a], 1);
+    /// ```
+    pub fn remove_and_trim(&mut self, i: Index<T, I, G>) -> Option<T> {
+        let value = self.remove(i)?;
+        let slot = i.index.to_idx();
+        if self.all_free_from(slot + 1) {
+            self.shrink_to(0);
+        }
+        Some(value)
+    }
+
+    /// Remove the element in the highest occupied slot, returning its index
+    /// and value, or `None` if the arena is empty.
+    ///
+    /// The index becomes stale as with [`remove`](#method.remove), and the
+    /// trailing free slots are released as with
+    /// [`remove_and_trim`](#method.remove_and_trim), so that the next
+    /// [`push`](#method.push) reuses the slot.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let a = arena.push(1);
+    /// let b = arena.push(2);
+    ///
+    /// assert_eq!(arena.pop(), Some((b, 2)));
+    /// assert!(!arena.contains(b));
+    /// assert_eq!(arena[a], 1);
+    /// ```
+    pub fn pop(&mut self) -> Option<(Index<T, I, G>, T)> {
+        let (i, _) = self.iter().next_back()?;
+        self.remove_and_trim(i).map(|value| (i, value))
+    }
+
+    /// Choose whether removals are deferred until
+    /// [`commit_removals`](#method.commit_removals).
+    ///
+    /// While removal is deferred, [`remove`](#method.remove) and the methods
+    /// built on it, such as [`retain`](#method.retain), only mark the slot
+    /// free: the removed element's index goes stale immediately, but the
+    /// arena's generation is not advanced and the slot is not put back on
+    /// the free list, so it cannot be reused and insertions may grow the
+    /// arena instead. Committing then advances the generation once and frees
+    /// every marked slot together, which suits write-heavy workloads that
+    /// remove in bursts. [`shrink_to`](#method.shrink_to) and
+    /// [`repair_free_list`](#method.repair_free_list) commit pending
+    /// removals first, and turning deferral off commits them too.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::with_capacity(2);
+    /// arena.set_deferred_removal(true);
+    /// let a = arena.insert(1);
+    /// arena.insert(2);
+    ///
+    /// arena.remove(a);
+    /// assert!(!arena.contains(a));
+    /// assert!(!arena.has_free());
+    ///
+    /// assert_eq!(arena.commit_removals(), 1);
+    /// assert!(arena.has_free());
+    /// ```
+    pub fn set_deferred_removal(&mut self, deferred: bool) {
+        if !deferred {
+            self.link_pending_removals();
+        }
+        self.deferred_removal = deferred;
+    }
+
+    /// Is removal deferred until
+    /// [`commit_removals`](#method.commit_removals)?
+    pub fn deferred_removal(&self) -> bool {
+        self.deferred_removal
+    }
+
+    /// Make the slots of the elements removed while removal was deferred
+    /// available for reuse, returning how many were freed.
+    ///
+    /// See [`set_deferred_removal`](#method.set_deferred_removal).
+    pub fn commit_removals(&mut self) -> usize {
+        self.link_pending_removals()
+    }
+
+    /// Remove the elements for which `predicate` returns `true`, yielding
+    /// them as they are removed.
+    ///
+    /// Elements are visited lazily in slot order, so a consumer that stops
+    /// early does no more work than it needs to. Dropping the iterator
+    /// removes the remaining matching elements, so once it is gone no
+    /// element satisfying `predicate` is left, however much of it was
+    /// consumed. Non-matching elements are kept. Leaking the iterator, for
+    /// example with `mem::forget`, leaves the remaining matches in place.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// for i in 0..6 {
+    ///     arena.insert(i);
+    /// }
+    ///
+    /// let first: Vec<_> = arena.drain_where(|v| v % 2 == 0).take(1).map(|(_, v)| v).collect();
+    /// assert_eq!(first, vec![0]);
+    /// assert_eq!(arena.len(), 3);
+    /// ```
+    pub fn drain_where<F>(&mut self, predicate: F) -> DrainWhere<'_, T, I, G, F>
+    where
+        F: Fn(&T) -> bool,
+    {
+        DrainWhere {
+            arena: self,
+            slot: 0,
+            predicate,
+        }
+    }
+
+    /// Insert `value`, evicting the element in the lowest occupied slot if the
+    /// arena has reached the limit set by [`set_max_len`](#method.set_max_len).
+    ///
+    /// This makes the arena a fixed-size cache which never grows past its
+    /// limit. The new element takes the evicted element's slot and the latest
+    /// generation, so it is the next to be evicted unless a lower slot is
+    /// filled first, and the evicted index becomes stale as with
+    /// [`remove`](#method.remove). Returns the new index, along with the
+    /// evicted index and value if there was an eviction.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the arena is empty and cannot accept an element, such as
+    /// when its maximum length is zero, as [`insert`](#method.insert) would.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// arena.set_max_len(1);
+    /// let a = arena.insert(1);
+    ///
+    /// let (b, evicted) = arena.insert_or_evict(2);
+    /// assert_eq!(evicted, Some((a, 1)));
+    /// assert!(!arena.contains(a));
+    /// assert_eq!(arena[b], 2);
+    /// ```
+    pub fn insert_or_evict(&mut self, value: T) -> (Index<T, I, G>, Evicted<T, I, G>) {
+        let oldest = if self.is_at_max_len() {
+            self.iter().next().map(|(idx, _)| idx)
+        } else {
+            None
+        };
+        let oldest = match oldest {
+            Some(oldest) => oldest,
+            None => return (self.insert(value), None),
+        };
+        self.generation.increment_generation();
+        self.version += 1;
+        self.removals += 1;
+        let slot = oldest.index.to_idx();
+        let entry = mem::replace(
+            &mut self.items[slot],
+            Entry::Occupied {
+                generation: self.generation,
+                value,
+            },
+        );
+        let evicted = match entry {
+            Entry::Occupied { value, .. } => value,
+            Entry::Free { .. } | Entry::Retired => unreachable!(),
+        };
+        (
+            Index::new(oldest.index, self.generation),
+            Some((oldest, evicted)),
+        )
+    }
+
+    /// Remove the element at index `i` only if `predicate` returns `true` for
+    /// it.
+    ///
+    /// If `i` does not resolve, or the predicate rejects the element, the
+    /// arena is left untouched and `None` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let idx = arena.insert(42);
+    ///
+    /// assert_eq!(arena.remove_if(idx, |v| *v == 7), None);
+    /// assert_eq!(arena.remove_if(idx, |v| *v == 42), Some(42));
+    /// assert!(!arena.contains(idx));
+    /// ```
+    pub fn remove_if(
+        &mut self,
+        i: Index<T, I, G>,
+        predicate: impl FnOnce(&T) -> bool,
+    ) -> Option<T> {
+        match self.get(i) {
+            Some(value) if predicate(value) => self.remove(i),
+            _ => None,
+        }
+    }
+
+    /// Retains only the elements specified by the predicate.
+    ///
+    /// In other words, remove all indices such that `predicate(index, &value)` returns `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut crew = StandardArena::new();
+    /// crew.extend(&["Jim Hawkins", "John Silver", "Alexander Smollett", "Israel Hands"]);
+    /// let pirates = ["John Silver", "Israel Hands"]; // too dangerous to keep them around
+    /// crew.retain(|_index, member| !pirates.contains(member));
+    /// let mut crew_members = crew.iter().map(|(_, member)| **member);
+    /// assert_eq!(crew_members.next(), Some("Jim Hawkins"));
+    /// assert_eq!(crew_members.next(), Some("Alexander Smollett"));
+    /// assert!(crew_members.next().is_none());
+    /// ```
+    pub fn retain(&mut self, mut predicate: impl FnMut(Index<T, I, G>, &T) -> bool) {
+        for i in 0..self.items.len() {
+            let remove = match &self.items[i] {
+                Entry::Occupied { generation, value } => {
+                    let index = Index::new(I::from_idx(i), *generation);
+                    if predicate(index, value) {
+                        None
+                    } else {
+                        Some(index)
+                    }
+                }
+                _ => None,
+            };
+            if let Some(index) = remove {
+                self.remove(index);
+            }
+        }
+    }
+
+    /// Retain only the elements for which `predicate` returns `true`, then
+    /// release any free slots left at the end of the arena.
+    ///
+    /// This is [`retain`](#method.retain) followed by
+    /// [`shrink_to(0)`](#method.shrink_to), so the indices of the surviving
+    /// elements stay valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::with_capacity(4);
+    /// let indices: Vec<_> = (0..4).map(|i| arena.insert(i)).collect();
+    ///
+    /// arena.retain_and_trim(|_, v| *v < 2);
+    /// assert_eq!(arena.capacity(), 2);
+    /// assert_eq!(arena[indices[1]], 1);
+    /// ```
+    pub fn retain_and_trim(&mut self, predicate: impl FnMut(Index<T, I, G>, &T) -> bool) {
+        self.retain(predicate);
+        self.shrink_to(0);
+    }
+
+    /// Keep only the first `n` elements in slot order, removing the rest.
+    ///
+    /// Like `Vec::truncate`, but counting only live elements. Capacity is
+    /// unchanged, and the removed elements' indices become stale as with
+    /// [`remove`](#method.remove). Does nothing if the arena holds `n` or
+    /// fewer elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let a = arena.insert(1);
+    /// let b = arena.insert(2);
+    ///
+    /// arena.truncate_live(1);
+    /// assert!(arena.contains(a));
+    /// assert!(!arena.contains(b));
+    /// ```
+    pub fn truncate_live(&mut self, n: usize) {
+        let mut kept = 0;
+        for i in 0..self.items.len() {
+            if self.len <= n {
+                break;
+            }
+            if let Entry::Occupied { generation, .. } = self.items[i] {
+                if kept < n {
+                    kept += 1;
+                } else {
+                    self.remove(Index::new(I::from_idx(i), generation));
+                }
+            }
+        }
     }
 
-    /// Returns true if the arena contains no elements
+    /// Keep, replace or remove every element in a single pass.
+    ///
+    /// `f` is given each element by value. Returning `Some(value)` writes
+    /// `value` back into the same slot with the same generation, so existing
+    /// indices stay valid; returning `None` removes the element. Returns the
+    /// number of elements removed.
     ///
     /// # Examples
     ///
@@ -299,257 +3270,347 @@ impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> Arena<T, I, G> {
     /// use generational_arena_im::StandardArena;
     ///
     /// let mut arena = StandardArena::new();
-    /// assert!(arena.is_empty());
-    ///
-    /// let idx = arena.insert(42);
-    /// assert!(!arena.is_empty());
+    /// let one = arena.insert(1);
+    /// let two = arena.insert(2);
     ///
-    /// assert_eq!(arena.remove(idx), Some(42));
-    /// assert!(arena.is_empty());
+    /// let removed = arena.retain_map(|_, v| if v % 2 == 0 { Some(v * 10) } else { None });
+    /// assert_eq!(removed, 1);
+    /// assert!(!arena.contains(one));
+    /// assert_eq!(arena[two], 20);
     /// ```
-    pub fn is_empty(&self) -> bool {
-        self.len == 0
+    pub fn retain_map(&mut self, mut f: impl FnMut(Index<T, I, G>, T) -> Option<T>) -> usize {
+        let mut removed = 0;
+        for i in 0..self.items.len() {
+            if !matches!(self.items[i], Entry::Occupied { .. }) {
+                continue;
+            }
+            let entry = mem::replace(&mut self.items[i], Entry::Free { next_free: None });
+            let (generation, value) = match entry {
+                Entry::Occupied { generation, value } => (generation, value),
+                Entry::Free { .. } | Entry::Retired => unreachable!(),
+            };
+            // `f` owns the value now, so if it panics the element is gone and
+            // its slot must be freed to keep the bookkeeping consistent.
+            let guard = RemoveOnUnwind {
+                arena: &mut *self,
+                slot: I::from_idx(i),
+            };
+            let kept = f(Index::new(I::from_idx(i), generation), value);
+            mem::forget(guard);
+            match kept {
+                Some(value) => self.items[i] = Entry::Occupied { generation, value },
+                None => {
+                    self.finish_removal(I::from_idx(i));
+                    removed += 1;
+                }
+            }
+        }
+        removed
     }
 
-    /// Get the capacity of this arena.
+    /// Make every outstanding index stale while keeping the elements,
+    /// returning the old and new index of each element.
     ///
-    /// The capacity is the maximum number of elements the arena can hold
-    /// without further allocation, including however many it currently
-    /// contains.
+    /// The arena's generation is advanced and given to every element, as if
+    /// each had been removed and reinserted into the same slot. Callers
+    /// holding indices can re-key them with the returned pairs, which are in
+    /// slot order. Since every element now has the latest generation,
+    /// [`iter_since`](#method.iter_since) treats them all as newly inserted.
     ///
     /// # Examples
     ///
     /// ```
     /// use generational_arena_im::StandardArena;
     ///
-    /// let mut arena = StandardArena::with_capacity(10);
-    /// assert_eq!(arena.capacity(), 10);
-    ///
-    /// // `try_insert` does not allocate new capacity.
-    /// for i in 0..10 {
-    ///     assert!(arena.try_insert(1).is_ok());
-    ///     assert_eq!(arena.capacity(), 10);
-    /// }
+    /// let mut arena = StandardArena::new();
+    /// let old = arena.insert("session");
     ///
-    /// // But `insert` will if the arena is already at capacity.
-    /// arena.insert(0);
-    /// assert!(arena.capacity() > 10);
+    /// let remap = arena.rotate_generations();
+    /// assert!(!arena.contains(old));
+    /// assert_eq!(remap[0].0, old);
+    /// assert_eq!(arena[remap[0].1], "session");
     /// ```
-    pub fn capacity(&self) -> usize {
-        self.items.len()
+    pub fn rotate_generations(&mut self) -> Vec<Remap<T, I, G>> {
+        self.generation.increment_generation();
+        self.version += 1;
+        let new_generation = self.generation;
+        let mut remap = Vec::with_capacity(self.len);
+        for (slot, entry) in self.items.iter_mut().enumerate() {
+            if let Entry::Occupied { generation, .. } = entry {
+                let index = I::from_idx(slot);
+                remap.push((
+                    Index::new(index, *generation),
+                    Index::new(index, new_generation),
+                ));
+                *generation = new_generation;
+            }
+        }
+        remap
     }
 
-    /// Allocate space for `additional_capacity` more elements in the arena.
+    /// Move the element at index `i` to the free slot `target`, returning
+    /// its new index.
     ///
-    /// # Panics
+    /// This gives manual control over the layout of the arena, for example
+    /// to move a frequently used element to slot 0. The old slot is freed as
+    /// if the element had been removed, so `i` becomes stale, and the
+    /// element gets a new generation in `target`.
     ///
-    /// Panics if this causes the capacity to overflow.
+    /// Returns `Err(RelocateError::Lookup)` if `i` is stale or out of bounds,
+    /// and `Err(RelocateError::TargetNotFree)` if `target` cannot be reused
+    /// or lies beyond the arena's capacity. The arena is unchanged on error.
     ///
     /// # Examples
     ///
     /// ```
     /// use generational_arena_im::StandardArena;
     ///
-    /// let mut arena = StandardArena::with_capacity(10);
-    /// arena.reserve(5);
-    /// assert_eq!(arena.capacity(), 15);
-    /// # let _: StandardArena<usize> = arena;
+    /// let mut arena = StandardArena::with_capacity(4);
+    /// let a = arena.insert("a");
+    /// let b = arena.insert("b");
+    /// arena.remove(a);
+    ///
+    /// let moved = arena.relocate(b, 0).unwrap();
+    /// assert_eq!(moved.to_idx(), 0);
+    /// assert_eq!(arena[moved], "b");
+    /// assert!(!arena.contains(b));
     /// ```
-    pub fn reserve(&mut self, additional_capacity: usize) {
-        let start = self.items.len();
-        let end = self.items.len() + additional_capacity;
-        let old_head = self.free_list_head;
-        for i in start..end {
-            let entry = if i == end - 1 {
-                Entry::Free {
-                    next_free: old_head,
-                }
-            } else {
-                Entry::Free {
-                    next_free: Some(I::from_idx(i + 1)),
-                }
-            };
-            self.items.push_back(entry);
+    pub fn relocate(
+        &mut self,
+        i: Index<T, I, G>,
+        target: I,
+    ) -> Result<Index<T, I, G>, RelocateError> {
+        let slot = self.lookup_slot(i).map_err(RelocateError::Lookup)?;
+        let target_slot = target.to_idx();
+        let target_free = self
+            .items
+            .get(target_slot)
+            .is_some_and(|entry| entry.is_reusable())
+            && !self
+                .pending_removals
+                .iter()
+                .any(|pending| pending.to_idx() == target_slot);
+        if !target_free {
+            return Err(RelocateError::TargetNotFree);
         }
-        self.free_list_head = Some(I::from_idx(start));
+
+        self.unlink_free_slot(target_slot);
+        let value = match mem::replace(&mut self.items[slot], Entry::Free { next_free: None }) {
+            Entry::Occupied { value, .. } => value,
+            Entry::Free { .. } | Entry::Retired => unreachable!(),
+        };
+        self.free_removed_slot(i.index);
+        let generation = self.generation;
+        self.items[target_slot] = Entry::Occupied { generation, value };
+        self.version += 1;
+        Ok(Index::new(target, generation))
     }
+}
 
-    /// Iterate over shared references to the elements in this arena.
-    ///
-    /// Yields pairs of `(Index<T>, &T)` items.
+impl<T: Clone, I: ArenaIndex> Arena<T, I, DisableRemoval> {
+    /// Slabs do not support removal, so this always returns
+    /// `Err(RemovalUnsupported)` and leaves the slab unchanged.
     ///
-    /// Order of iteration is not defined.
+    /// It exists so that code written against arenas which do support
+    /// removal fails with a clear error rather than a trait-bound error.
     ///
     /// # Examples
     ///
     /// ```
-    /// use generational_arena_im::StandardArena;
-    ///
-    /// let mut arena = StandardArena::new();
-    /// for i in 0..10 {
-    ///     arena.insert(i * i);
-    /// }
+    /// use generational_arena_im::{RemovalUnsupported, StandardSlab};
     ///
-    /// for (idx, value) in arena.iter() {
-    ///     println!("{} is at index {:?}", value, idx);
-    /// }
+    /// let mut slab = StandardSlab::new();
+    /// let idx = slab.insert(42);
+    /// assert_eq!(slab.remove(idx), Err(RemovalUnsupported));
+    /// assert_eq!(slab[idx], 42);
     /// ```
-    pub fn iter(&self) -> Iter<T, I, G> {
-        Iter {
-            len: self.len,
-            inner: self.items.iter().enumerate(),
-        }
+    pub fn remove(
+        &mut self,
+        _i: Index<T, I, DisableRemoval>,
+    ) -> Result<Option<T>, RemovalUnsupported> {
+        Err(RemovalUnsupported)
     }
 
-    /// Iterate over exclusive references to the elements in this arena.
+    /// Iterate over the elements of this slab in the order they were
+    /// inserted.
     ///
-    /// Yields pairs of `(Index<T>, &mut T)` items.
+    /// A slab never frees a slot, and inserting fills its free slots lowest
+    /// first, so insertion order is slot order and this is the same as
+    /// [`iter`](#method.iter). That holds as long as the slab's free slots
+    /// are only ever created by [`new`](#method.new),
+    /// [`with_capacity`](#method.with_capacity) or growth on insertion.
+    /// Reserving capacity while free slots remain puts the new slots first in
+    /// line, and [`insert_contiguous`](#method.insert_contiguous),
+    /// [`push`](#method.push) and [`insert_below`](#method.insert_below) can
+    /// skip over free slots, so these break the correspondence.
     ///
-    /// Order of iteration is not defined.
+    /// Arenas which support removal reuse the slots of removed elements, so
+    /// they do not remember insertion order and have no such method.
     ///
     /// # Examples
     ///
     /// ```
-    /// use generational_arena_im::StandardArena;
+    /// use generational_arena_im::StandardSlab;
     ///
-    /// let mut arena = StandardArena::new();
-    /// for i in 0..10 {
-    ///     arena.insert(i * i);
+    /// let mut slab = StandardSlab::new();
+    /// for word in &["first", "second", "third"] {
+    ///     slab.insert(*word);
     /// }
     ///
-    /// for (_idx, value) in arena.iter_mut() {
-    ///     *value += 5;
-    /// }
+    /// let words: Vec<_> = slab.iter_insertion_order().map(|(_, w)| *w).collect();
+    /// assert_eq!(words, vec!["first", "second", "third"]);
     /// ```
-    pub fn iter_mut(&mut self) -> IterMut<T, I, G> {
-        IterMut {
-            len: self.len,
-            inner: self.items.iter_mut().enumerate(),
-        }
+    pub fn iter_insertion_order(&self) -> Iter<'_, T, I, DisableRemoval> {
+        self.iter()
     }
+}
 
-    /// Iterate over elements of the arena and remove them.
+/// An `Arena<()>` is a generational set of handles: each insertion yields a
+/// handle that is unique among live elements, and removing it makes the
+/// handle stale for good (up to generation wrapping).
+impl<I: ArenaIndex, G: FixedGenerationalIndex> Arena<(), I, G> {
+    /// Allocate a new handle.
     ///
-    /// Yields pairs of `(Index<T>, T)` items.
+    /// This is shorthand for `insert(())`.
     ///
-    /// Order of iteration is not defined.
+    /// # Panics
     ///
-    /// Note: All elements are removed even if the iterator is only partially consumed or not consumed at all.
+    /// Panics under the same conditions as [`insert`](#method.insert).
     ///
     /// # Examples
     ///
     /// ```
     /// use generational_arena_im::StandardArena;
     ///
-    /// let mut arena = StandardArena::new();
-    /// let idx_1 = arena.insert("hello");
-    /// let idx_2 = arena.insert("world");
+    /// let mut handles = StandardArena::<()>::new();
+    /// let key = handles.insert_key();
+    /// assert!(handles.contains(key));
     ///
-    /// assert!(arena.get(idx_1).is_some());
-    /// assert!(arena.get(idx_2).is_some());
-    /// for (idx, value) in arena.drain() {
-    ///     assert!((idx == idx_1 && value == "hello") || (idx == idx_2 && value == "world"));
-    /// }
-    /// assert!(arena.get(idx_1).is_none());
-    /// assert!(arena.get(idx_2).is_none());
+    /// handles.remove(key);
+    /// assert!(!handles.contains(key));
     /// ```
-    pub fn drain(&mut self) -> Drain<T, I, G> {
-        let old_items = mem::take(&mut self.items);
-        let cap = old_items.len();
-        self.len = 0;
-        self.free_list_head = None;
-        self.reserve(cap);
-        Drain {
-            inner: old_items.into_iter().enumerate(),
-        }
+    pub fn insert_key(&mut self) -> Index<(), I, G> {
+        self.insert(())
     }
 }
 
-impl<T: Clone, I: ArenaIndex, G: GenerationalIndex> Arena<T, I, G> {
-    /// Remove the element at index `i` from the arena.
+#[cfg(feature = "std")]
+impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> Arena<T, I, G> {
+    /// Remove every element whose index is in `set`, returning how many were
+    /// removed.
     ///
-    /// If the element at index `i` is still in the arena, then it is
-    /// returned. If it is not in the arena, then `None` is returned.
+    /// This walks the arena once, so it is cheaper than removing the indices
+    /// one by one when `set` is large. Indices in `set` that are not in the
+    /// arena are ignored.
     ///
     /// # Examples
     ///
     /// ```
     /// use generational_arena_im::StandardArena;
+    /// use std::collections::HashSet;
     ///
     /// let mut arena = StandardArena::new();
-    /// let idx = arena.insert(42);
+    /// let a = arena.insert(1);
+    /// let b = arena.insert(2);
     ///
-    /// assert_eq!(arena.remove(idx), Some(42));
-    /// assert_eq!(arena.remove(idx), None);
+    /// let set: HashSet<_> = vec![a].into_iter().collect();
+    /// assert_eq!(arena.remove_set(&set), 1);
+    /// assert!(!arena.contains(a));
+    /// assert!(arena.contains(b));
     /// ```
-    pub fn remove(&mut self, i: Index<T, I, G>) -> Option<T> {
-        if i.index.to_idx() >= self.items.len() {
-            return None;
-        }
-
-        let entry = mem::replace(
-            &mut self.items[i.index.to_idx()],
-            Entry::Free {
-                next_free: self.free_list_head,
-            },
-        );
-        match entry {
-            Entry::Occupied { generation, value } => {
-                if generation == i.generation {
-                    self.generation.increment_generation();
-                    self.free_list_head = Some(i.index);
-                    self.len -= 1;
-                    Some(value)
-                } else {
-                    self.items[i.index.to_idx()] = Entry::Occupied { generation, value };
-                    None
-                }
-            }
-            e @ Entry::Free { .. } => {
-                self.items[i.index.to_idx()] = e;
-                None
-            }
+    pub fn remove_set(&mut self, set: &std::collections::HashSet<Index<T, I, G>>) -> usize
+    where
+        I: Hash + Eq,
+        G: GenerationalIndex + Hash,
+    {
+        let before = self.len;
+        if !set.is_empty() {
+            self.retain(|idx, _| !set.contains(&idx));
         }
+        before - self.len
     }
 
-    /// Retains only the elements specified by the predicate.
+    /// Clone the elements of this arena into a `HashMap` keyed by slot.
     ///
-    /// In other words, remove all indices such that `predicate(index, &value)` returns `false`.
+    /// Generations are discarded, so the map cannot tell a stale index from
+    /// a live one at the same slot.
     ///
     /// # Examples
     ///
     /// ```
     /// use generational_arena_im::StandardArena;
     ///
-    /// let mut crew = StandardArena::new();
-    /// crew.extend(&["Jim Hawkins", "John Silver", "Alexander Smollett", "Israel Hands"]);
-    /// let pirates = ["John Silver", "Israel Hands"]; // too dangerous to keep them around
-    /// crew.retain(|_index, member| !pirates.contains(member));
-    /// let mut crew_members = crew.iter().map(|(_, member)| **member);
-    /// assert_eq!(crew_members.next(), Some("Jim Hawkins"));
-    /// assert_eq!(crew_members.next(), Some("Alexander Smollett"));
-    /// assert!(crew_members.next().is_none());
+    /// let mut arena = StandardArena::new();
+    /// let idx = arena.insert("a");
+    ///
+    /// let map = arena.to_hashmap();
+    /// assert_eq!(map[&idx.to_idx()], "a");
     /// ```
-    pub fn retain(&mut self, mut predicate: impl FnMut(Index<T, I, G>, &T) -> bool) {
-        for i in 0..self.items.len() {
-            let remove = match &self.items[i] {
-                Entry::Occupied { generation, value } => {
-                    let index = Index::new(I::from_idx(i), *generation);
-                    if predicate(index, value) {
-                        None
-                    } else {
-                        Some(index)
-                    }
-                }
-                _ => None,
-            };
-            if let Some(index) = remove {
-                self.remove(index);
-            }
+    pub fn to_hashmap(&self) -> std::collections::HashMap<usize, T> {
+        self.iter()
+            .map(|(idx, value)| (idx.to_idx(), value.clone()))
+            .collect()
+    }
+
+    /// Move the elements of this arena into a `HashMap` keyed by slot.
+    ///
+    /// Like [`to_hashmap`](#method.to_hashmap), but consumes the arena
+    /// instead of cloning its elements.
+    pub fn into_hashmap(self) -> std::collections::HashMap<usize, T> {
+        self.into_iter_indexed()
+            .map(|(idx, value)| (idx.to_idx(), value))
+            .collect()
+    }
+}
+
+impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> Default for Arena<T, I, G> {
+    fn default() -> Self {
+        Arena::new()
+    }
+}
+
+/// Arenas compare equal when they hold equal values at the same slots.
+///
+/// Generations and free-list layout are not taken into account.
+impl<T: Clone + PartialEq, I: ArenaIndex, G: FixedGenerationalIndex> PartialEq for Arena<T, I, G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.live_slots().eq(other.live_slots())
+    }
+}
+
+impl<T: Clone + Eq, I: ArenaIndex, G: FixedGenerationalIndex> Eq for Arena<T, I, G> {}
+
+/// Arenas are ordered lexicographically by their `(slot, value)` pairs, in
+/// slot order.
+impl<T: Clone + PartialOrd, I: ArenaIndex, G: FixedGenerationalIndex> PartialOrd
+    for Arena<T, I, G>
+{
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        self.live_slots().partial_cmp(other.live_slots())
+    }
+}
+
+impl<T: Clone + Ord, I: ArenaIndex, G: FixedGenerationalIndex> Ord for Arena<T, I, G> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.live_slots().cmp(other.live_slots())
+    }
+}
+
+impl<T: Clone + Hash, I: ArenaIndex, G: FixedGenerationalIndex> Hash for Arena<T, I, G> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        for pair in self.live_slots() {
+            pair.hash(state);
         }
     }
 }
 
+impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> Arena<T, I, G> {
+    fn live_slots(&self) -> impl Iterator<Item = (usize, &T)> + '_ {
+        self.iter().map(|(idx, value)| (idx.to_idx(), value))
+    }
+}
+
 impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> IntoIterator for Arena<T, I, G> {
     type Item = T;
     type IntoIter = IntoIter<T, I, G>;
@@ -598,7 +3659,7 @@ impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> Iterator for IntoIter<T
     fn next(&mut self) -> Option<Self::Item> {
         loop {
             match self.inner.next() {
-                Some(Entry::Free { .. }) => continue,
+                Some(Entry::Free { .. }) | Some(Entry::Retired) => continue,
                 Some(Entry::Occupied { value, .. }) => {
                     self.len -= 1;
                     return Some(value);
@@ -620,7 +3681,7 @@ impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> DoubleEndedIterator for
     fn next_back(&mut self) -> Option<Self::Item> {
         loop {
             match self.inner.next_back() {
-                Some(Entry::Free { .. }) => continue,
+                Some(Entry::Free { .. }) | Some(Entry::Retired) => continue,
                 Some(Entry::Occupied { value, .. }) => {
                     self.len -= 1;
                     return Some(value);
@@ -691,7 +3752,7 @@ impl<'a, T: Clone, I: 'a + ArenaIndex, G: 'a + FixedGenerationalIndex> Iterator
     fn next(&mut self) -> Option<Self::Item> {
         loop {
             match self.inner.next() {
-                Some((_, &Entry::Free { .. })) => continue,
+                Some((_, &Entry::Free { .. })) | Some((_, &Entry::Retired)) => continue,
                 Some((
                     index,
                     &Entry::Occupied {
@@ -722,7 +3783,7 @@ impl<'a, T: Clone, I: 'a + ArenaIndex, G: 'a + FixedGenerationalIndex> DoubleEnd
     fn next_back(&mut self) -> Option<Self::Item> {
         loop {
             match self.inner.next_back() {
-                Some((_, &Entry::Free { .. })) => continue,
+                Some((_, &Entry::Free { .. })) | Some((_, &Entry::Retired)) => continue,
                 Some((
                     index,
                     &Entry::Occupied {
@@ -804,7 +3865,7 @@ impl<'a, T: Clone, I: 'a + ArenaIndex, G: 'a + FixedGenerationalIndex> Iterator
     fn next(&mut self) -> Option<Self::Item> {
         loop {
             match self.inner.next() {
-                Some((_, &mut Entry::Free { .. })) => continue,
+                Some((_, &mut Entry::Free { .. })) | Some((_, &mut Entry::Retired)) => continue,
                 Some((
                     index,
                     &mut Entry::Occupied {
@@ -835,7 +3896,7 @@ impl<'a, T: Clone, I: 'a + ArenaIndex, G: 'a + FixedGenerationalIndex> DoubleEnd
     fn next_back(&mut self) -> Option<Self::Item> {
         loop {
             match self.inner.next_back() {
-                Some((_, &mut Entry::Free { .. })) => continue,
+                Some((_, &mut Entry::Free { .. })) | Some((_, &mut Entry::Retired)) => continue,
                 Some((
                     index,
                     &mut Entry::Occupied {
@@ -910,7 +3971,7 @@ impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> Iterator for Drain<T, I
     fn next(&mut self) -> Option<Self::Item> {
         loop {
             match self.inner.next() {
-                Some((_, Entry::Free { .. })) => continue,
+                Some((_, Entry::Free { .. })) | Some((_, Entry::Retired)) => continue,
                 Some((index, Entry::Occupied { generation, value })) => {
                     let idx = Index::new(I::from_idx(index), generation);
                     return Some((idx, value));
@@ -921,6 +3982,98 @@ impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> Iterator for Drain<T, I
     }
 }
 
+/// An iterator that removes the elements of an arena matching a predicate.
+///
+/// Yields pairs of `(Index<T>, T)` items in slot order. The remaining
+/// matches are removed when the iterator is dropped.
+///
+/// Created by [`Arena::drain_where`](struct.Arena.html#method.drain_where).
+pub struct DrainWhere<'a, T, I, G, F>
+where
+    T: Clone,
+    I: ArenaIndex,
+    G: GenerationalIndex,
+    F: Fn(&T) -> bool,
+{
+    arena: &'a mut Arena<T, I, G>,
+    slot: usize,
+    predicate: F,
+}
+
+impl<'a, T, I, G, F> core::fmt::Debug for DrainWhere<'a, T, I, G, F>
+where
+    T: Clone,
+    I: ArenaIndex,
+    G: GenerationalIndex,
+    F: Fn(&T) -> bool,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("DrainWhere")
+            .field("slot", &self.slot)
+            .finish()
+    }
+}
+
+impl<'a, T, I, G, F> Iterator for DrainWhere<'a, T, I, G, F>
+where
+    T: Clone,
+    I: ArenaIndex,
+    G: GenerationalIndex,
+    F: Fn(&T) -> bool,
+{
+    type Item = (Index<T, I, G>, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.slot < self.arena.items.len() {
+            let slot = self.slot;
+            self.slot += 1;
+            let idx = match &self.arena.items[slot] {
+                Entry::Occupied { generation, value } if (self.predicate)(value) => {
+                    Index::new(I::from_idx(slot), *generation)
+                }
+                _ => continue,
+            };
+            return self.arena.remove(idx).map(|value| (idx, value));
+        }
+        None
+    }
+}
+
+impl<'a, T, I, G, F> FusedIterator for DrainWhere<'a, T, I, G, F>
+where
+    T: Clone,
+    I: ArenaIndex,
+    G: GenerationalIndex,
+    F: Fn(&T) -> bool,
+{
+}
+
+impl<'a, T, I, G, F> Drop for DrainWhere<'a, T, I, G, F>
+where
+    T: Clone,
+    I: ArenaIndex,
+    G: GenerationalIndex,
+    F: Fn(&T) -> bool,
+{
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+/// Frees a slot whose element was moved out, unless forgotten first; used
+/// by `Arena::retain_map` and `Arena::map_in_place` to stay consistent if
+/// their closure panics.
+struct RemoveOnUnwind<'a, T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> {
+    arena: &'a mut Arena<T, I, G>,
+    slot: I,
+}
+
+impl<'a, T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> Drop for RemoveOnUnwind<'a, T, I, G> {
+    fn drop(&mut self) {
+        self.arena.finish_removal(self.slot);
+    }
+}
+
 impl<T: Clone, Idx: ArenaIndex, G: FixedGenerationalIndex> Extend<T> for Arena<T, Idx, G> {
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
         for t in iter {
@@ -941,6 +4094,8 @@ impl<T: Clone, Idx: ArenaIndex, G: FixedGenerationalIndex> FromIterator<T> for A
     }
 }
 
+/// Indexing panics if the element is not in the arena; use
+/// [`Arena::get`](struct.Arena.html#method.get) for fallible lookup.
 impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> ops::Index<Index<T, I, G>>
     for Arena<T, I, G>
 {
@@ -951,6 +4106,8 @@ impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> ops::Index<Index<T, I,
     }
 }
 
+/// Indexing panics if the element is not in the arena; use
+/// [`Arena::get_mut`](struct.Arena.html#method.get_mut) for fallible lookup.
 impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> ops::IndexMut<Index<T, I, G>>
     for Arena<T, I, G>
 {
@@ -958,3 +4115,65 @@ impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> ops::IndexMut<Index<T,
         self.get_mut(index).expect("No element at index")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StandardArena;
+
+    fn corrupt_link(arena: &mut StandardArena<usize>, slot: usize, next_free: Option<usize>) {
+        arena.items[slot] = Entry::Free { next_free };
+    }
+
+    #[test]
+    fn repair_cyclic_free_list() {
+        let mut arena = StandardArena::with_capacity(4);
+        let indices: Vec<_> = (0..4).map(|i| arena.insert(i)).collect();
+        arena.remove(indices[1]);
+        arena.remove(indices[2]);
+        // 2 -> 1 -> 2 -> ...
+        corrupt_link(&mut arena, 1, Some(2));
+
+        assert_eq!(arena.repair_free_list(), 1);
+        assert_eq!(arena.repair_free_list(), 0);
+        let slots: Vec<_> = (0..2)
+            .map(|i| arena.try_insert(i).unwrap().to_idx())
+            .collect();
+        assert_eq!(slots, [1, 2]);
+        assert!(arena.try_insert(0).is_err());
+    }
+
+    #[test]
+    fn repair_self_linked_slot() {
+        let mut arena = StandardArena::with_capacity(4);
+        let indices: Vec<_> = (0..4).map(|i| arena.insert(i)).collect();
+        arena.remove(indices[2]);
+        // 2 -> 2 -> ...
+        corrupt_link(&mut arena, 2, Some(2));
+
+        assert_eq!(arena.repair_free_list(), 1);
+        assert_eq!(arena.try_insert(7).unwrap().to_idx(), 2);
+        assert!(arena.try_insert(0).is_err());
+    }
+
+    #[test]
+    fn repair_out_of_range_link_and_len() {
+        let mut arena = StandardArena::with_capacity(4);
+        let indices: Vec<_> = (0..4).map(|i| arena.insert(i)).collect();
+        arena.remove(indices[0]);
+        arena.remove(indices[3]);
+        corrupt_link(&mut arena, 3, Some(100));
+        corrupt_link(&mut arena, 1, None);
+        arena.len = 7;
+
+        // Wrong length, a bad link, and the orphaned slots 0 and 1.
+        assert_eq!(arena.repair_free_list(), 4);
+        assert_eq!(arena.len(), 1);
+        assert_eq!(arena[indices[2]], 2);
+        assert_eq!(arena.iter().count(), 1);
+        for i in 0..3 {
+            arena.try_insert(i).unwrap();
+        }
+        assert!(arena.try_insert(0).is_err());
+    }
+}
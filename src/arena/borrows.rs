@@ -0,0 +1,155 @@
+use super::*;
+use im::vector::Focus;
+
+/// A builder for borrowing one element of an arena mutably while borrowing
+/// others immutably.
+///
+/// Created by [`Arena::borrows`](struct.Arena.html#method.borrows).
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena_im::StandardArena;
+///
+/// let mut arena = StandardArena::new();
+/// let a = arena.insert(1);
+/// let b = arena.insert(2);
+/// let c = arena.insert(3);
+///
+/// let (a_mut, shared) = arena.borrows().mutable(a).shared(b).shared(c).resolve();
+/// *a_mut.unwrap() = *shared[0].unwrap() + *shared[1].unwrap();
+/// assert_eq!(arena[a], 5);
+/// ```
+pub struct Borrows<'a, T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> {
+    arena: &'a mut Arena<T, I, G>,
+    mutable: Option<Index<T, I, G>>,
+    shared: Vec<Index<T, I, G>>,
+}
+
+impl<'a, T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> core::fmt::Debug
+    for Borrows<'a, T, I, G>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Borrows")
+            .field("shared", &self.shared.len())
+            .finish()
+    }
+}
+
+impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> Arena<T, I, G> {
+    /// Start building a set of borrows into this arena: at most one mutable
+    /// borrow and any number of shared ones.
+    ///
+    /// See [`Borrows`](struct.Borrows.html) for details.
+    pub fn borrows(&mut self) -> Borrows<'_, T, I, G> {
+        Borrows {
+            arena: self,
+            mutable: None,
+            shared: Vec::new(),
+        }
+    }
+}
+
+impl<'a, T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> Borrows<'a, T, I, G> {
+    /// Request a mutable borrow of the element at index `i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a mutable borrow was already requested.
+    pub fn mutable(mut self, i: Index<T, I, G>) -> Self {
+        assert!(
+            self.mutable.is_none(),
+            "only one mutable borrow may be requested"
+        );
+        self.mutable = Some(i);
+        self
+    }
+
+    /// Request a shared borrow of the element at index `i`.
+    ///
+    /// Shared borrows may refer to the same element as each other.
+    pub fn shared(mut self, i: Index<T, I, G>) -> Self {
+        self.shared.push(i);
+        self
+    }
+
+    /// Resolve the requested borrows.
+    ///
+    /// Returns the mutable borrow, followed by the shared borrows in the order
+    /// they were requested. Each is `None` if its index is not in the arena.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a shared borrow refers to the same slot as the mutable
+    /// borrow, regardless of generation. See
+    /// [`try_resolve`](#method.try_resolve) for a non-panicking alternative.
+    pub fn resolve(self) -> (Option<&'a mut T>, Vec<Option<&'a T>>) {
+        self.try_resolve()
+            .expect("shared borrow aliases the mutable borrow")
+    }
+
+    /// Resolve the requested borrows, or return `None` if a shared borrow
+    /// refers to the same slot as the mutable borrow.
+    pub fn try_resolve(self) -> Option<(Option<&'a mut T>, Vec<Option<&'a T>>)> {
+        let Borrows {
+            arena,
+            mutable,
+            shared,
+        } = self;
+        let focus = arena.items.focus_mut();
+        let mutable = match mutable {
+            Some(i) if i.index.to_idx() < focus.len() => i,
+            _ => {
+                let focus = focus.unmut();
+                let shared = shared
+                    .iter()
+                    .map(|i| resolve_shared(&focus, i.index.to_idx(), i.generation))
+                    .collect();
+                return Some((None, shared));
+            }
+        };
+
+        let slot = mutable.index.to_idx();
+        if shared.iter().any(|i| i.index.to_idx() == slot) {
+            return None;
+        }
+        let (left, rest) = focus.split_at(slot);
+        let (middle, right) = rest.split_at(1);
+        let (left, right) = (left.unmut(), right.unmut());
+        let value = match middle.into_iter().next() {
+            Some(Entry::Occupied { generation, value }) if *generation == mutable.generation => {
+                Some(value)
+            }
+            _ => None,
+        };
+        let shared = shared
+            .iter()
+            .map(|i| {
+                let other = i.index.to_idx();
+                if other < slot {
+                    resolve_shared(&left, other, i.generation)
+                } else {
+                    resolve_shared(&right, other - slot - 1, i.generation)
+                }
+            })
+            .collect();
+        Some((value, shared))
+    }
+}
+
+fn resolve_shared<'a, T: Clone, I: ArenaIndex, G: FixedGenerationalIndex>(
+    focus: &Focus<'a, Entry<T, I, G>>,
+    slot: usize,
+    generation: G,
+) -> Option<&'a T> {
+    if slot >= focus.len() {
+        return None;
+    }
+    match focus.clone().narrow(slot..slot + 1).into_iter().next() {
+        Some(Entry::Occupied {
+            generation: g,
+            value,
+        }) if *g == generation => Some(value),
+        _ => None,
+    }
+}
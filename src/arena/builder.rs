@@ -0,0 +1,116 @@
+use super::*;
+
+/// The order in which an arena reuses the slots of removed elements.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FreePolicy {
+    /// Reuse the most recently freed slot first. This is the default.
+    Lifo,
+    /// Reuse the least recently freed slot first, which delays the reuse of
+    /// any particular slot for as long as possible.
+    Fifo,
+}
+
+/// How much capacity an arena adds when it is full and an element is
+/// inserted.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum GrowthStrategy {
+    /// Double the capacity. This is the default.
+    Double,
+    /// Add this many slots, or one slot if this is zero.
+    Additive(usize),
+    /// Add a single slot, so the capacity never exceeds the number of
+    /// elements ever held at once.
+    Exact,
+}
+
+/// A builder for an `Arena` with non-default behavior.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena_im::{ArenaBuilder, FreePolicy, GrowthStrategy, StandardArena};
+///
+/// let mut arena: StandardArena<u32> = ArenaBuilder::new()
+///     .capacity(64)
+///     .free_policy(FreePolicy::Fifo)
+///     .growth(GrowthStrategy::Additive(16))
+///     .max_len(50)
+///     .no_grow()
+///     .build();
+///
+/// assert_eq!(arena.capacity(), 64);
+/// assert_eq!(arena.max_len(), Some(50));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ArenaBuilder {
+    capacity: usize,
+    free_policy: FreePolicy,
+    growth: GrowthStrategy,
+    max_len: Option<usize>,
+    no_grow: bool,
+}
+
+impl ArenaBuilder {
+    /// Start configuring an arena with the same settings as `Arena::new`.
+    pub fn new() -> ArenaBuilder {
+        ArenaBuilder {
+            capacity: DEFAULT_CAPACITY,
+            free_policy: FreePolicy::Lifo,
+            growth: GrowthStrategy::Double,
+            max_len: None,
+            no_grow: false,
+        }
+    }
+
+    /// Preallocate space for `capacity` elements.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Choose the order in which the slots of removed elements are reused.
+    pub fn free_policy(mut self, free_policy: FreePolicy) -> Self {
+        self.free_policy = free_policy;
+        self
+    }
+
+    /// Choose how much capacity is added when the arena grows, as with
+    /// [`Arena::set_growth`](struct.Arena.html#method.set_growth).
+    pub fn growth(mut self, growth: GrowthStrategy) -> Self {
+        self.growth = growth;
+        self
+    }
+
+    /// Limit the number of elements the arena may hold, as with
+    /// [`Arena::set_max_len`](struct.Arena.html#method.set_max_len).
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Never grow the arena when inserting into it while it is full.
+    ///
+    /// `insert` panics and `checked_insert` returns the value back instead.
+    /// Capacity can still be added explicitly with
+    /// [`Arena::reserve`](struct.Arena.html#method.reserve).
+    pub fn no_grow(mut self) -> Self {
+        self.no_grow = true;
+        self
+    }
+
+    /// Construct an arena with the configured behavior.
+    pub fn build<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex>(&self) -> Arena<T, I, G> {
+        let mut arena = Arena::with_capacity(self.capacity);
+        arena.free_policy = self.free_policy;
+        arena.growth = self.growth;
+        arena.max_len = self.max_len;
+        arena.no_grow = self.no_grow;
+        arena
+    }
+}
+
+impl Default for ArenaBuilder {
+    fn default() -> Self {
+        ArenaBuilder::new()
+    }
+}
@@ -0,0 +1,187 @@
+use super::*;
+use crate::encode::{Decode, Encode};
+use crate::error::DecodeError;
+
+const FREE_TAG: u8 = 0;
+const OCCUPIED_TAG: u8 = 1;
+const RETIRED_TAG: u8 = 2;
+
+// A free list link is encoded as 0 for the end of the list, or as the slot it
+// points to plus one.
+fn encode_link<I: ArenaIndex>(link: Option<I>, out: &mut Vec<u8>) {
+    link.map_or(0, |slot| slot.to_idx() + 1).encode(out);
+}
+
+fn decode_link<I: ArenaIndex>(
+    input: &mut &[u8],
+    capacity: usize,
+) -> Result<Option<I>, DecodeError> {
+    match usize::decode(input)? {
+        0 => Ok(None),
+        n if n - 1 < capacity => I::try_from_idx(n - 1)
+            .map(Some)
+            .ok_or(DecodeError::InvalidValue),
+        _ => Err(DecodeError::InvalidValue),
+    }
+}
+
+impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> Arena<T, I, G> {
+    /// Append a compact binary encoding of this arena to `out`.
+    ///
+    /// This is an alternative to serde for targets that cannot depend on it.
+    /// The encoding starts with the number of elements, the arena's
+    /// generation and its capacity, followed by every slot in order: a tag
+    /// byte saying whether it is free, occupied or retired, then either its
+    /// free list link or the element's generation and value. Integers are little-endian,
+    /// with `usize` always taking 64 bits. Every index into the arena remains
+    /// valid in the arena returned by [`decode`](#method.decode), but settings
+    /// such as [`set_max_len`](#method.set_max_len) are not encoded. Neither
+    /// are uncommitted deferred removals, so call
+    /// [`commit_removals`](#method.commit_removals) first if their slots
+    /// should be reusable in the decoded arena.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::<u32>::new();
+    /// let idx = arena.insert(7);
+    ///
+    /// let mut bytes = Vec::new();
+    /// arena.encode(&mut bytes);
+    ///
+    /// let decoded = StandardArena::<u32>::decode(&bytes).unwrap();
+    /// assert_eq!(decoded[idx], 7);
+    /// ```
+    pub fn encode(&self, out: &mut Vec<u8>)
+    where
+        T: Encode,
+        G: Encode,
+    {
+        self.len.encode(out);
+        self.generation.encode(out);
+        self.items.len().encode(out);
+        encode_link(self.free_list_head, out);
+        for entry in &self.items {
+            match entry {
+                Entry::Free { next_free } => {
+                    out.push(FREE_TAG);
+                    encode_link(*next_free, out);
+                }
+                Entry::Occupied { generation, value } => {
+                    out.push(OCCUPIED_TAG);
+                    generation.encode(out);
+                    value.encode(out);
+                }
+                Entry::Retired => out.push(RETIRED_TAG),
+            }
+        }
+    }
+
+    /// Encode every element as a fixed-size record, for formats such as
+    /// memory-mapped files where each record must have the same layout.
+    ///
+    /// Yields the `W` bytes that `encode` produces for each element together
+    /// with the element's slot and generation, in slot order. Free slots are
+    /// skipped, so the records alone do not describe the free list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let idx = arena.insert(7u32);
+    ///
+    /// let records: Vec<_> = arena.to_records(|v| v.to_le_bytes()).collect();
+    /// assert_eq!(records, vec![([7, 0, 0, 0], idx.to_idx(), idx.gen())]);
+    /// ```
+    pub fn to_records<'a, const W: usize>(
+        &'a self,
+        encode: impl Fn(&T) -> [u8; W] + 'a,
+    ) -> impl Iterator<Item = ([u8; W], usize, G)> + 'a {
+        self.items
+            .iter()
+            .enumerate()
+            .filter_map(move |(slot, entry)| match entry {
+                Entry::Occupied { generation, value } => Some((encode(value), slot, *generation)),
+                Entry::Free { .. } | Entry::Retired => None,
+            })
+    }
+
+    /// Decode an arena from the encoding written by
+    /// [`encode`](#method.encode).
+    ///
+    /// Returns an error if `bytes` is not exactly one encoded arena, including
+    /// when its free list does not link every free slot exactly once.
+    pub fn decode(bytes: &[u8]) -> Result<Arena<T, I, G>, DecodeError>
+    where
+        T: Decode,
+        G: Decode,
+    {
+        let input = &mut &*bytes;
+        let len = usize::decode(input)?;
+        let generation = G::decode(input)?;
+        let capacity = usize::decode(input)?;
+        let free_list_head: Option<I> = decode_link(input, capacity)?;
+
+        let mut items = Vector::new();
+        let mut occupied = 0;
+        let mut free = 0;
+        for _ in 0..capacity {
+            let entry = match u8::decode(input)? {
+                FREE_TAG => {
+                    free += 1;
+                    Entry::Free {
+                        next_free: decode_link(input, capacity)?,
+                    }
+                }
+                OCCUPIED_TAG => {
+                    occupied += 1;
+                    Entry::Occupied {
+                        generation: G::decode(input)?,
+                        value: T::decode(input)?,
+                    }
+                }
+                RETIRED_TAG => Entry::Retired,
+                tag => return Err(DecodeError::InvalidTag(tag)),
+            };
+            items.push_back(entry);
+        }
+        if occupied != len {
+            return Err(DecodeError::InvalidValue);
+        }
+        if !input.is_empty() {
+            return Err(DecodeError::TrailingBytes);
+        }
+        // The free list must visit every free slot exactly once and nothing
+        // else. A link to a used slot or a cycle would otherwise surface later
+        // as a panic or an endless loop in `insert`.
+        let mut cur = free_list_head;
+        let mut free_list_tail = None;
+        let mut linked = 0;
+        while let Some(slot) = cur {
+            if linked == free {
+                return Err(DecodeError::InvalidValue);
+            }
+            cur = match &items[slot.to_idx()] {
+                Entry::Free { next_free } => *next_free,
+                Entry::Occupied { .. } | Entry::Retired => return Err(DecodeError::InvalidValue),
+            };
+            free_list_tail = Some(slot);
+            linked += 1;
+        }
+        if linked != free {
+            return Err(DecodeError::InvalidValue);
+        }
+
+        let mut arena = Arena::empty();
+        arena.items = items;
+        arena.generation = generation;
+        arena.len = len;
+        arena.free_list_head = free_list_head;
+        arena.free_list_tail = free_list_tail;
+        Ok(arena)
+    }
+}
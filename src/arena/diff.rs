@@ -0,0 +1,400 @@
+use super::*;
+use im::vector::Focus;
+
+/// A single difference between two versions of an arena.
+///
+/// Produced by [`Arena::diff`](struct.Arena.html#method.diff) and
+/// [`Arena::changes_since`](struct.Arena.html#method.changes_since), which
+/// describe how to get from an older snapshot to the current arena.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArenaOp<'a, T, I = usize, G = usize> {
+    /// An element was inserted at a slot that was free in the snapshot.
+    Insert(Index<T, I, G>, &'a T),
+    /// The element of the snapshot with this index was removed.
+    Remove(Index<T, I, G>),
+    /// The element with this index is still present, but its value changed.
+    Change(Index<T, I, G>, &'a T),
+}
+
+impl<'a, T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> ArenaOp<'a, T, I, G> {
+    /// Clone the value this operation refers to, if any, so the operation
+    /// can outlive the arena it was computed from.
+    pub fn into_owned(self) -> OwnedArenaOp<T, I, G> {
+        match self {
+            ArenaOp::Insert(i, value) => OwnedArenaOp::Insert(i.index, i.generation, value.clone()),
+            ArenaOp::Remove(i) => OwnedArenaOp::Remove(i),
+            ArenaOp::Change(i, value) => OwnedArenaOp::Change(i, value.clone()),
+        }
+    }
+}
+
+/// How a single slot changed between two versions of an arena.
+///
+/// Produced by
+/// [`Arena::slot_states_zip`](struct.Arena.html#method.slot_states_zip).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotDelta<'a, T> {
+    /// The slot holds the same element in both versions, or is free in both.
+    Same,
+    /// The slot was occupied in the older version and is free in the newer.
+    BecameFree,
+    /// The slot was free in the older version and holds this value in the
+    /// newer.
+    BecameOccupied(&'a T),
+    /// The slot is occupied in both versions, but by a different element or
+    /// a changed value. This is the newer value.
+    ValueChanged(&'a T),
+}
+
+/// An operation to replay onto an arena with
+/// [`Arena::apply_ops`](struct.Arena.html#method.apply_ops).
+///
+/// This is the owned counterpart of [`ArenaOp`](enum.ArenaOp.html), suitable
+/// for storing in an operation log.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OwnedArenaOp<T, I = usize, G = usize> {
+    /// Insert a value at the given slot with the given generation.
+    Insert(I, G, T),
+    /// Remove the element with this index.
+    Remove(Index<T, I, G>),
+    /// Replace the value of the element with this index.
+    Change(Index<T, I, G>, T),
+}
+
+impl<T: Clone, I: ArenaIndex, G: GenerationalIndex> Arena<T, I, G> {
+    /// Apply a log of operations, such as one recorded with
+    /// [`diff`](#method.diff), to this arena.
+    ///
+    /// Operations which do not fit the arena's current state are skipped:
+    /// removing or changing an element that is not in the arena, or
+    /// inserting into a slot that is occupied, retired, or cannot be
+    /// addressed. Returns the number of operations skipped, so zero means the
+    /// whole log applied cleanly.
+    ///
+    /// Inserting beyond the current capacity grows the arena to fit. The
+    /// arena's generation counter is advanced to any newer inserted
+    /// generation, so later insertions never get older generations than the
+    /// replayed ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let a = arena.insert(1);
+    /// let mut replica = arena.clone();
+    ///
+    /// arena[a] = 2;
+    /// arena.insert(3);
+    /// let log: Vec<_> = arena.diff(&replica).into_iter().map(|op| op.into_owned()).collect();
+    ///
+    /// assert_eq!(replica.apply_ops(log), 0);
+    /// assert_eq!(replica, arena);
+    /// ```
+    pub fn apply_ops<It>(&mut self, ops: It) -> usize
+    where
+        It: IntoIterator<Item = OwnedArenaOp<T, I, G>>,
+    {
+        let mut skipped = 0;
+        for op in ops {
+            let applied = match op {
+                OwnedArenaOp::Insert(index, generation, value) => {
+                    self.insert_at(index, generation, value)
+                }
+                OwnedArenaOp::Remove(i) => self.remove(i).is_some(),
+                OwnedArenaOp::Change(i, value) => match self.get_mut(i) {
+                    Some(slot) => {
+                        *slot = value;
+                        true
+                    }
+                    None => false,
+                },
+            };
+            if !applied {
+                skipped += 1;
+            }
+        }
+        skipped
+    }
+
+    /// Occupy the free slot `index` with `value` at `generation`, growing
+    /// the arena if the slot is beyond its capacity.
+    fn insert_at(&mut self, index: I, generation: G, value: T) -> bool {
+        let slot = index.to_idx();
+        if slot >= self.items.len() && self.try_reserve(slot + 1 - self.items.len()).is_err() {
+            return false;
+        }
+        if !self.items[slot].is_reusable() {
+            return false;
+        }
+        self.unlink_free_slot(slot);
+        self.items[slot] = Entry::Occupied { generation, value };
+        self.len += 1;
+        self.version += 1;
+        if self.generation.generation_lt(&generation) {
+            self.generation = generation;
+        }
+        true
+    }
+}
+
+impl<T: Clone + PartialEq, I: ArenaIndex, G: FixedGenerationalIndex> Arena<T, I, G> {
+    /// Lazily compute the operations that turn `snapshot` into `self`.
+    ///
+    /// Slots are visited in ascending order. A slot whose element was
+    /// removed and replaced by a new one yields a `Remove` followed by an
+    /// `Insert`. Runs of slots that `self` still shares with `snapshot`
+    /// (because neither was mutated there since cloning) are skipped without
+    /// comparing their elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::{ArenaOp, StandardArena};
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let a = arena.insert(1);
+    /// let snapshot = arena.clone();
+    ///
+    /// arena[a] = 2;
+    /// let b = arena.insert(3);
+    ///
+    /// let ops: Vec<_> = arena.changes_since(&snapshot).collect();
+    /// assert_eq!(ops, vec![ArenaOp::Change(a, &2), ArenaOp::Insert(b, &3)]);
+    /// ```
+    pub fn changes_since<'a>(
+        &'a self,
+        snapshot: &'a Self,
+    ) -> impl Iterator<Item = ArenaOp<'a, T, I, G>> + 'a {
+        Changes {
+            new: &self.items,
+            old: &snapshot.items,
+            new_focus: self.items.focus(),
+            old_focus: snapshot.items.focus(),
+            slot: 0,
+            end: cmp::max(self.items.len(), snapshot.items.len()),
+            compare_until: 0,
+            pending: None,
+        }
+    }
+
+    /// Zip the slots of `self` with those of a newer version `other`,
+    /// yielding each slot's position together with how it changed.
+    ///
+    /// Every slot up to the larger of the two capacities is visited in
+    /// ascending order; slots beyond an arena's capacity count as free. A
+    /// slot occupied in both versions is `ValueChanged` if its value differs
+    /// or if it holds a different element (one with a different generation).
+    /// Free list links are not compared. Runs of slots that the two versions
+    /// still share are yielded as `Same` without comparing their elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::{SlotDelta, StandardArena};
+    ///
+    /// let mut arena = StandardArena::with_capacity(2);
+    /// let a = arena.insert(1);
+    /// let snapshot = arena.clone();
+    ///
+    /// arena.remove(a);
+    /// arena.insert(2);
+    /// arena.insert(3);
+    ///
+    /// let deltas: Vec<_> = snapshot.slot_states_zip(&arena).collect();
+    /// assert_eq!(
+    ///     deltas,
+    ///     vec![(0, SlotDelta::ValueChanged(&2)), (1, SlotDelta::BecameOccupied(&3))]
+    /// );
+    /// ```
+    pub fn slot_states_zip<'a>(
+        &'a self,
+        other: &'a Self,
+    ) -> impl Iterator<Item = (usize, SlotDelta<'a, T>)> + 'a {
+        SlotStatesZip {
+            old: &self.items,
+            new: &other.items,
+            old_focus: self.items.focus(),
+            new_focus: other.items.focus(),
+            slot: 0,
+            end: cmp::max(self.items.len(), other.items.len()),
+            compared_until: 0,
+            shared_until: 0,
+        }
+    }
+
+    /// Compute the operations that turn `snapshot` into `self`.
+    ///
+    /// This is the eager counterpart of
+    /// [`changes_since`](#method.changes_since).
+    pub fn diff<'a>(&'a self, snapshot: &'a Self) -> Vec<ArenaOp<'a, T, I, G>> {
+        self.changes_since(snapshot).collect()
+    }
+}
+
+struct Changes<'a, T, I, G> {
+    new: &'a Vector<Entry<T, I, G>>,
+    old: &'a Vector<Entry<T, I, G>>,
+    new_focus: Focus<'a, Entry<T, I, G>>,
+    old_focus: Focus<'a, Entry<T, I, G>>,
+    slot: usize,
+    end: usize,
+    /// Slots below this have already been checked for structural sharing.
+    compare_until: usize,
+    pending: Option<ArenaOp<'a, T, I, G>>,
+}
+
+impl<'a, T, I, G> Changes<'a, T, I, G>
+where
+    T: Clone,
+    I: ArenaIndex,
+    G: FixedGenerationalIndex,
+{
+    /// If the chunk containing the current slot is shared by both versions,
+    /// skip past it.
+    fn skip_shared_chunk(&mut self) {
+        if self.slot < self.compare_until
+            || self.slot >= self.new.len()
+            || self.slot >= self.old.len()
+        {
+            return;
+        }
+        let (new_range, new_chunk) = self.new_focus.chunk_at(self.slot);
+        let (old_range, old_chunk) = self.old_focus.chunk_at(self.slot);
+        if new_range == old_range && core::ptr::eq(new_chunk, old_chunk) {
+            self.slot = new_range.end;
+        } else {
+            self.compare_until = cmp::min(new_range.end, old_range.end);
+        }
+    }
+}
+
+impl<'a, T, I, G> Iterator for Changes<'a, T, I, G>
+where
+    T: Clone + PartialEq,
+    I: ArenaIndex,
+    G: FixedGenerationalIndex,
+{
+    type Item = ArenaOp<'a, T, I, G>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(op) = self.pending.take() {
+            return Some(op);
+        }
+        loop {
+            self.skip_shared_chunk();
+            if self.slot >= self.end {
+                return None;
+            }
+            let slot = self.slot;
+            self.slot += 1;
+            let index = |generation| Index::new(I::from_idx(slot), generation);
+            match (self.old.get(slot), self.new.get(slot)) {
+                (
+                    Some(Entry::Occupied {
+                        generation: old_gen,
+                        value: old_value,
+                    }),
+                    Some(Entry::Occupied { generation, value }),
+                ) => {
+                    if old_gen != generation {
+                        self.pending = Some(ArenaOp::Insert(index(*generation), value));
+                        return Some(ArenaOp::Remove(index(*old_gen)));
+                    } else if old_value != value {
+                        return Some(ArenaOp::Change(index(*generation), value));
+                    }
+                }
+                (Some(Entry::Occupied { generation, .. }), _) => {
+                    return Some(ArenaOp::Remove(index(*generation)));
+                }
+                (_, Some(Entry::Occupied { generation, value })) => {
+                    return Some(ArenaOp::Insert(index(*generation), value));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+struct SlotStatesZip<'a, T, I, G> {
+    old: &'a Vector<Entry<T, I, G>>,
+    new: &'a Vector<Entry<T, I, G>>,
+    old_focus: Focus<'a, Entry<T, I, G>>,
+    new_focus: Focus<'a, Entry<T, I, G>>,
+    slot: usize,
+    end: usize,
+    /// Slots below this have already been checked for structural sharing.
+    compared_until: usize,
+    /// Slots below this are in a chunk shared by both versions.
+    shared_until: usize,
+}
+
+impl<'a, T, I, G> SlotStatesZip<'a, T, I, G>
+where
+    T: Clone,
+    I: ArenaIndex,
+    G: FixedGenerationalIndex,
+{
+    /// Check whether the chunk containing the current slot is shared by both
+    /// versions, unless that has already been done.
+    fn check_shared_chunk(&mut self) {
+        if self.slot < self.compared_until
+            || self.slot >= self.new.len()
+            || self.slot >= self.old.len()
+        {
+            return;
+        }
+        let (new_range, new_chunk) = self.new_focus.chunk_at(self.slot);
+        let (old_range, old_chunk) = self.old_focus.chunk_at(self.slot);
+        if new_range == old_range && core::ptr::eq(new_chunk, old_chunk) {
+            self.shared_until = new_range.end;
+        }
+        self.compared_until = cmp::min(new_range.end, old_range.end);
+    }
+}
+
+impl<'a, T, I, G> Iterator for SlotStatesZip<'a, T, I, G>
+where
+    T: Clone + PartialEq,
+    I: ArenaIndex,
+    G: FixedGenerationalIndex,
+{
+    type Item = (usize, SlotDelta<'a, T>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.slot >= self.end {
+            return None;
+        }
+        self.check_shared_chunk();
+        let slot = self.slot;
+        self.slot += 1;
+        if slot < self.shared_until {
+            return Some((slot, SlotDelta::Same));
+        }
+        let delta = match (self.old.get(slot), self.new.get(slot)) {
+            (
+                Some(Entry::Occupied {
+                    generation: old_gen,
+                    value: old_value,
+                }),
+                Some(Entry::Occupied { generation, value }),
+            ) => {
+                if old_gen != generation || old_value != value {
+                    SlotDelta::ValueChanged(value)
+                } else {
+                    SlotDelta::Same
+                }
+            }
+            (Some(Entry::Occupied { .. }), _) => SlotDelta::BecameFree,
+            (_, Some(Entry::Occupied { value, .. })) => SlotDelta::BecameOccupied(value),
+            _ => SlotDelta::Same,
+        };
+        Some((slot, delta))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.slot;
+        (remaining, Some(remaining))
+    }
+}
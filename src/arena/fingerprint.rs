@@ -0,0 +1,104 @@
+use super::*;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// A 64-bit FNV-1a hasher which writes integers as little-endian bytes, and
+/// `usize` and `isize` as 64 bits, so that its output does not depend on the
+/// platform.
+struct Fnv1a(u64);
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16);
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32);
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128);
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_u64(i as i64 as u64);
+    }
+}
+
+impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> Arena<T, I, G> {
+    /// Compute a 64-bit fingerprint of the arena's contents which is the same
+    /// in every process and on every platform.
+    ///
+    /// Unlike the `Hash` implementation, whose output depends on the
+    /// `Hasher` and often on a per-process random seed, this always hashes
+    /// the slot, generation and value of each element in slot order with
+    /// unseeded 64-bit FNV-1a, so fingerprints can be compared across
+    /// machines to find duplicate arenas. Integers are hashed as
+    /// little-endian, with `usize` widened to 64 bits. The fingerprint is
+    /// only as stable as the `Hash` implementations of `T` and `G`, and is
+    /// not suitable where collisions could be provoked deliberately.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut a = StandardArena::new();
+    /// let mut b = StandardArena::new();
+    /// a.insert("x");
+    /// b.insert("x");
+    /// assert_eq!(a.content_fingerprint(), b.content_fingerprint());
+    ///
+    /// b.insert("y");
+    /// assert_ne!(a.content_fingerprint(), b.content_fingerprint());
+    /// ```
+    pub fn content_fingerprint(&self) -> u64
+    where
+        T: Hash,
+        G: Hash,
+    {
+        let mut hasher = Fnv1a(FNV_OFFSET_BASIS);
+        for (slot, idx, value) in self.iter_physical() {
+            slot.hash(&mut hasher);
+            idx.generation.hash(&mut hasher);
+            value.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+}
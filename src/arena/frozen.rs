@@ -0,0 +1,116 @@
+use super::rayon::ParIter;
+use super::*;
+use rayon::iter::IntoParallelIterator;
+
+/// A read-only view of an arena.
+///
+/// A `FrozenArena` offers lookups and iteration but no way to insert, remove
+/// or mutate elements, so it can be handed out without risk of accidental
+/// modification. Created by [`Arena::freeze`](struct.Arena.html#method.freeze);
+/// call [`thaw`](#method.thaw) to get the mutable arena back. Both are O(1),
+/// and cloning a `FrozenArena` is as cheap as cloning an `Arena`.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena_im::StandardArena;
+///
+/// let mut arena = StandardArena::new();
+/// let idx = arena.insert(1);
+///
+/// let frozen = arena.freeze();
+/// assert_eq!(frozen.get(idx), Some(&1));
+/// ```
+///
+/// A frozen arena cannot be mutated:
+///
+/// ```compile_fail
+/// use generational_arena_im::StandardArena;
+///
+/// let mut arena = StandardArena::new();
+/// let idx = arena.insert(1);
+///
+/// let mut frozen = arena.freeze();
+/// frozen.remove(idx);
+/// ```
+#[derive(Clone, Debug)]
+pub struct FrozenArena<T: Clone, I: Clone = usize, G: Clone = usize> {
+    arena: Arena<T, I, G>,
+}
+
+impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> Arena<T, I, G> {
+    /// Turn this arena into a read-only [`FrozenArena`](struct.FrozenArena.html).
+    pub fn freeze(self) -> FrozenArena<T, I, G> {
+        FrozenArena { arena: self }
+    }
+}
+
+impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> FrozenArena<T, I, G> {
+    /// Turn this view back into a mutable arena.
+    pub fn thaw(self) -> Arena<T, I, G> {
+        self.arena
+    }
+
+    /// Get a shared reference to the element at index `i` if it is in the
+    /// arena.
+    pub fn get(&self, i: Index<T, I, G>) -> Option<&T> {
+        self.arena.get(i)
+    }
+
+    /// Is the element at index `i` in the arena?
+    pub fn contains(&self, i: Index<T, I, G>) -> bool {
+        self.arena.contains(i)
+    }
+
+    /// Get the number of elements in the arena.
+    pub fn len(&self) -> usize {
+        self.arena.len()
+    }
+
+    /// Returns true if the arena contains no elements
+    pub fn is_empty(&self) -> bool {
+        self.arena.is_empty()
+    }
+
+    /// Iterate over shared references to the elements in the arena.
+    ///
+    /// Yields pairs of `(Index<T>, &T)` items, in the same order as
+    /// [`Arena::iter`](struct.Arena.html#method.iter).
+    pub fn iter(&self) -> Iter<'_, T, I, G> {
+        self.arena.iter()
+    }
+}
+
+impl<T, I, G> FrozenArena<T, I, G>
+where
+    T: Clone + Send + Sync,
+    I: ArenaIndex + Send + Sync,
+    G: FixedGenerationalIndex + Send + Sync,
+{
+    /// Iterate in parallel over shared references to the elements in the
+    /// arena.
+    pub fn par_iter(&self) -> ParIter<'_, T, I, G> {
+        (&self.arena).into_par_iter()
+    }
+}
+
+impl<'a, T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> IntoIterator
+    for &'a FrozenArena<T, I, G>
+{
+    type Item = (Index<T, I, G>, &'a T);
+    type IntoIter = Iter<'a, T, I, G>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> ops::Index<Index<T, I, G>>
+    for FrozenArena<T, I, G>
+{
+    type Output = T;
+
+    fn index(&self, index: Index<T, I, G>) -> &Self::Output {
+        &self.arena[index]
+    }
+}
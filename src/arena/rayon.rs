@@ -1,9 +1,35 @@
 use super::*;
+use crate::generation::DisableRemoval;
 use im::vector::{Focus, FocusMut, Iter as ImIter, IterMut as ImIterMut};
 use rayon::iter::plumbing::{bridge, Consumer, Producer, ProducerCallback};
-use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+use rayon::iter::{
+    Either, IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator,
+    IntoParallelRefMutIterator, ParallelIterator,
+};
+
+cfg_if! {
+    if #[cfg(feature = "std")] {
+        use std::collections::BTreeMap;
+    } else {
+        use alloc::collections::BTreeMap;
+    }
+}
+
+/// Number of slots per entry of the live counts used to split `par_iter` and
+/// `par_iter_mut` by elements.
+const LIVE_COUNT_CHUNK: usize = 64;
+
+/// An element of an arena together with its index.
+type Element<'a, T, I, G> = (Index<T, I, G>, &'a T);
 
 /// Parallel iterator over shared references to arena elements.
+///
+/// The work is split evenly by the number of elements. To find the slot to
+/// split at, creating this iterator on an arena with free slots first counts
+/// the elements in every chunk of 64 slots. That is a serial pass over every
+/// slot before any parallel work starts, and it allocates one `usize` per
+/// chunk. An arena without free slots skips it, since its slots and
+/// elements coincide.
 pub struct ParIter<'a, T, I, G>
 where
     T: Clone + Send + Sync + 'a,
@@ -11,11 +37,18 @@ where
     G: FixedGenerationalIndex + Send + Sync + 'a,
 {
     focus: Focus<'a, Entry<T, I, G>>,
-    start: usize,
+    counts: Option<Vec<usize>>,
     len: usize,
 }
 
 /// Parallel iterator over mutable references to arena elements.
+///
+/// The work is split evenly by the number of elements. To find the slot to
+/// split at, creating this iterator on an arena with free slots first counts
+/// the elements in every chunk of 64 slots. That is a serial pass over every
+/// slot before any parallel work starts, and it allocates one `usize` per
+/// chunk. An arena without free slots skips it, since its slots and
+/// elements coincide.
 pub struct ParIterMut<'a, T, I, G>
 where
     T: Clone + Send + Sync + 'a,
@@ -23,7 +56,7 @@ where
     G: FixedGenerationalIndex + Send + Sync + 'a,
 {
     focus: FocusMut<'a, Entry<T, I, G>>,
-    start: usize,
+    counts: Option<Vec<usize>>,
     len: usize,
 }
 
@@ -63,6 +96,10 @@ where
     {
         bridge(self, consumer)
     }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.len)
+    }
 }
 
 impl<'a, T, I, G> IndexedParallelIterator for ParIter<'a, T, I, G>
@@ -86,9 +123,17 @@ where
     where
         CB: ProducerCallback<Self::Item>,
     {
+        let focus = if self.len == 0 {
+            None
+        } else {
+            Some(self.focus)
+        };
         callback.callback(ArenaProducer {
-            focus: self.focus,
-            start: self.start,
+            focus,
+            start: 0,
+            rank: 0,
+            len: self.len,
+            counts: self.counts.as_deref(),
         })
     }
 }
@@ -107,6 +152,10 @@ where
     {
         bridge(self, consumer)
     }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.len)
+    }
 }
 
 impl<'a, T, I, G> IndexedParallelIterator for ParIterMut<'a, T, I, G>
@@ -130,9 +179,17 @@ where
     where
         CB: ProducerCallback<Self::Item>,
     {
+        let focus = if self.len == 0 {
+            None
+        } else {
+            Some(self.focus)
+        };
         callback.callback(ArenaMutProducer {
-            focus: self.focus,
-            start: self.start,
+            focus,
+            start: 0,
+            rank: 0,
+            len: self.len,
+            counts: self.counts.as_deref(),
         })
     }
 }
@@ -149,7 +206,7 @@ where
     fn into_par_iter(self) -> Self::Iter {
         ParIter {
             focus: self.items.focus(),
-            start: 0,
+            counts: self.live_counts(),
             len: self.len,
         }
     }
@@ -166,45 +223,111 @@ where
 
     fn into_par_iter(self) -> Self::Iter {
         let len = self.len;
+        let counts = self.live_counts();
         let focus = self.items.focus_mut();
-        ParIterMut {
-            focus,
-            start: 0,
-            len,
+        ParIterMut { focus, counts, len }
+    }
+}
+
+impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> Arena<T, I, G> {
+    /// The number of elements before every `LIVE_COUNT_CHUNK`th slot, or
+    /// `None` if every slot is occupied and slots and elements coincide.
+    ///
+    /// This walks every slot on the calling thread, so it is the serial part
+    /// of `par_iter` and `par_iter_mut` on arenas with free slots.
+    fn live_counts(&self) -> Option<Vec<usize>> {
+        if self.len == self.items.len() {
+            return None;
+        }
+        let mut counts = Vec::with_capacity(self.items.len() / LIVE_COUNT_CHUNK + 1);
+        let mut live = 0;
+        for (slot, entry) in self.items.iter().enumerate() {
+            if slot % LIVE_COUNT_CHUNK == 0 {
+                counts.push(live);
+            }
+            if let Entry::Occupied { .. } = entry {
+                live += 1;
+            }
+        }
+        Some(counts)
+    }
+}
+
+/// Find the offset from `start` of the slot holding element number `rank`.
+///
+/// `counts` are the live counts of the whole arena, `start_rank` is the
+/// number of elements before `start`, and `is_occupied` reports whether the
+/// slot at an offset from `start` is occupied.
+fn slot_of_rank(
+    counts: Option<&[usize]>,
+    start: usize,
+    start_rank: usize,
+    rank: usize,
+    mut is_occupied: impl FnMut(usize) -> bool,
+) -> usize {
+    let counts = match counts {
+        Some(counts) => counts,
+        None => return rank - start_rank,
+    };
+    // The element is in the last chunk that starts with `rank` or fewer
+    // elements before it, which may be the chunk `start` is in.
+    let chunk = counts.partition_point(|&count| count <= rank) - 1;
+    let (mut slot, mut seen) = if chunk * LIVE_COUNT_CHUNK > start {
+        (chunk * LIVE_COUNT_CHUNK, counts[chunk])
+    } else {
+        (start, start_rank)
+    };
+    loop {
+        if is_occupied(slot - start) {
+            if seen == rank {
+                return slot - start;
+            }
+            seen += 1;
         }
+        slot += 1;
     }
 }
 
-struct ArenaProducer<'a, T, I, G>
+/// A producer over the `len` elements in the slots
+/// `start..start + focus.len()`, the first of which is element number `rank`
+/// of the arena. An empty producer has no focus, since `im` cannot split off
+/// an empty one.
+struct ArenaProducer<'a, 'b, T, I, G>
 where
     T: Clone + Send + Sync,
     I: ArenaIndex + Send + Sync,
     G: FixedGenerationalIndex + Send + Sync,
 {
-    focus: Focus<'a, Entry<T, I, G>>,
+    focus: Option<Focus<'a, Entry<T, I, G>>>,
     start: usize,
+    rank: usize,
+    len: usize,
+    counts: Option<&'b [usize]>,
 }
 
-struct ArenaMutProducer<'a, T, I, G>
+struct ArenaMutProducer<'a, 'b, T, I, G>
 where
     T: Clone + Send + Sync,
     I: ArenaIndex + Send + Sync,
     G: FixedGenerationalIndex + Send + Sync,
 {
-    focus: FocusMut<'a, Entry<T, I, G>>,
+    focus: Option<FocusMut<'a, Entry<T, I, G>>>,
     start: usize,
+    rank: usize,
+    len: usize,
+    counts: Option<&'b [usize]>,
 }
 
 struct SeqIter<'a, T, I, G> {
     start: usize,
     len: usize,
-    inner: core::iter::Enumerate<ImIter<'a, Entry<T, I, G>>>,
+    inner: Option<core::iter::Enumerate<ImIter<'a, Entry<T, I, G>>>>,
 }
 
 struct SeqIterMut<'a, T, I, G> {
     start: usize,
     len: usize,
-    inner: core::iter::Enumerate<ImIterMut<'a, Entry<T, I, G>>>,
+    inner: Option<core::iter::Enumerate<ImIterMut<'a, Entry<T, I, G>>>>,
 }
 
 impl<'a, T, I, G> Iterator for SeqIter<'a, T, I, G>
@@ -216,9 +339,10 @@ where
     type Item = (Index<T, I, G>, &'a T);
 
     fn next(&mut self) -> Option<Self::Item> {
+        let inner = self.inner.as_mut()?;
         loop {
-            match self.inner.next() {
-                Some((_, &Entry::Free { .. })) => continue,
+            match inner.next() {
+                Some((_, &Entry::Free { .. })) | Some((_, &Entry::Retired)) => continue,
                 Some((
                     i,
                     &Entry::Occupied {
@@ -237,9 +361,9 @@ where
         }
     }
 
-    // fn size_hint(&self) -> (usize, Option<usize>) {
-    //     (self.len, Some(self.len))
-    // }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
 }
 
 impl<'a, T, I, G> ExactSizeIterator for SeqIter<'a, T, I, G>
@@ -260,9 +384,10 @@ where
     G: FixedGenerationalIndex,
 {
     fn next_back(&mut self) -> Option<Self::Item> {
+        let inner = self.inner.as_mut()?;
         loop {
-            match self.inner.next_back() {
-                Some((_, &Entry::Free { .. })) => continue,
+            match inner.next_back() {
+                Some((_, &Entry::Free { .. })) | Some((_, &Entry::Retired)) => continue,
                 Some((
                     i,
                     &Entry::Occupied {
@@ -291,9 +416,10 @@ where
     type Item = (Index<T, I, G>, &'a mut T);
 
     fn next(&mut self) -> Option<Self::Item> {
+        let inner = self.inner.as_mut()?;
         loop {
-            match self.inner.next() {
-                Some((_, &mut Entry::Free { .. })) => continue,
+            match inner.next() {
+                Some((_, &mut Entry::Free { .. })) | Some((_, &mut Entry::Retired)) => continue,
                 Some((
                     i,
                     &mut Entry::Occupied {
@@ -312,9 +438,9 @@ where
         }
     }
 
-    // fn size_hint(&self) -> (usize, Option<usize>) {
-    //     (self.len, Some(self.len))
-    // }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
 }
 
 impl<'a, T, I, G> ExactSizeIterator for SeqIterMut<'a, T, I, G>
@@ -335,9 +461,10 @@ where
     G: FixedGenerationalIndex,
 {
     fn next_back(&mut self) -> Option<Self::Item> {
+        let inner = self.inner.as_mut()?;
         loop {
-            match self.inner.next_back() {
-                Some((_, &mut Entry::Free { .. })) => continue,
+            match inner.next_back() {
+                Some((_, &mut Entry::Free { .. })) | Some((_, &mut Entry::Retired)) => continue,
                 Some((
                     i,
                     &mut Entry::Occupied {
@@ -357,7 +484,7 @@ where
     }
 }
 
-impl<'a, T, I, G> Producer for ArenaProducer<'a, T, I, G>
+impl<'a, 'b, T, I, G> Producer for ArenaProducer<'a, 'b, T, I, G>
 where
     T: Clone + Send + Sync + 'a,
     I: ArenaIndex + Send + Sync + 'a,
@@ -369,27 +496,48 @@ where
     fn into_iter(self) -> Self::IntoIter {
         SeqIter {
             start: self.start,
-            len: self.focus.len(),
-            inner: self.focus.into_iter().enumerate(),
+            len: self.len,
+            inner: self.focus.map(|focus| focus.into_iter().enumerate()),
         }
     }
 
     fn split_at(self, index: usize) -> (Self, Self) {
-        let (left, right) = self.focus.split_at(index);
+        let (left, right, offset) = match self.focus {
+            Some(focus) if index == self.len => (Some(focus), None, 0),
+            Some(focus) if index > 0 => {
+                let mut focus = focus;
+                let offset = slot_of_rank(
+                    self.counts,
+                    self.start,
+                    self.rank,
+                    self.rank + index,
+                    |offset| matches!(focus.get(offset), Some(Entry::Occupied { .. })),
+                );
+                let (left, right) = focus.split_at(offset);
+                (Some(left), Some(right), offset)
+            }
+            focus => (None, focus, 0),
+        };
         (
             ArenaProducer {
                 focus: left,
                 start: self.start,
+                rank: self.rank,
+                len: index,
+                counts: self.counts,
             },
             ArenaProducer {
                 focus: right,
-                start: self.start + index,
+                start: self.start + offset,
+                rank: self.rank + index,
+                len: self.len - index,
+                counts: self.counts,
             },
         )
     }
 }
 
-impl<'a, T, I, G> Producer for ArenaMutProducer<'a, T, I, G>
+impl<'a, 'b, T, I, G> Producer for ArenaMutProducer<'a, 'b, T, I, G>
 where
     T: Clone + Send + Sync + 'a,
     I: ArenaIndex + Send + Sync + 'a,
@@ -401,22 +549,814 @@ where
     fn into_iter(self) -> Self::IntoIter {
         SeqIterMut {
             start: self.start,
-            len: self.focus.len(),
-            inner: self.focus.into_iter().enumerate(),
+            len: self.len,
+            inner: self.focus.map(|focus| focus.into_iter().enumerate()),
         }
     }
 
     fn split_at(self, index: usize) -> (Self, Self) {
-        let (left, right) = self.focus.split_at(index);
+        let (left, right, offset) = match self.focus {
+            Some(focus) if index == self.len => (Some(focus), None, 0),
+            Some(focus) if index > 0 => {
+                let mut focus = focus;
+                let offset = slot_of_rank(
+                    self.counts,
+                    self.start,
+                    self.rank,
+                    self.rank + index,
+                    |offset| matches!(focus.get(offset), Some(Entry::Occupied { .. })),
+                );
+                let (left, right) = focus.split_at(offset);
+                (Some(left), Some(right), offset)
+            }
+            focus => (None, focus, 0),
+        };
         (
             ArenaMutProducer {
                 focus: left,
                 start: self.start,
+                rank: self.rank,
+                len: index,
+                counts: self.counts,
             },
             ArenaMutProducer {
                 focus: right,
-                start: self.start + index,
+                start: self.start + offset,
+                rank: self.rank + index,
+                len: self.len - index,
+                counts: self.counts,
             },
         )
     }
 }
+
+/// Parallel iterator over shared references to arena elements which splits
+/// its work evenly by the number of elements rather than by slots.
+///
+/// Created by [`Arena::par_iter_balanced`](../struct.Arena.html#method.par_iter_balanced)
+/// and [`Arena::par_iter_filtered`](../struct.Arena.html#method.par_iter_filtered).
+pub struct BalancedParIter<'a, T, I, G>
+where
+    T: Clone + Send + Sync + 'a,
+    I: ArenaIndex + Send + Sync + 'a,
+    G: FixedGenerationalIndex + Send + Sync + 'a,
+{
+    focus: Focus<'a, Entry<T, I, G>>,
+    /// The slots of the elements to produce, in ascending order.
+    live: Vec<usize>,
+}
+
+impl<'a, T, I, G> core::fmt::Debug for BalancedParIter<'a, T, I, G>
+where
+    T: Clone + Send + Sync + 'a,
+    I: ArenaIndex + Send + Sync + 'a,
+    G: FixedGenerationalIndex + Send + Sync + 'a,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("BalancedParIter")
+            .field("len", &self.live.len())
+            .finish()
+    }
+}
+
+impl<'a, T, I, G> ParallelIterator for BalancedParIter<'a, T, I, G>
+where
+    T: Clone + Send + Sync + 'a,
+    I: ArenaIndex + Send + Sync + 'a,
+    G: FixedGenerationalIndex + Send + Sync + 'a,
+{
+    type Item = (Index<T, I, G>, &'a T);
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: ::rayon::iter::plumbing::UnindexedConsumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        Some(self.live.len())
+    }
+}
+
+impl<'a, T, I, G> IndexedParallelIterator for BalancedParIter<'a, T, I, G>
+where
+    T: Clone + Send + Sync + 'a,
+    I: ArenaIndex + Send + Sync + 'a,
+    G: FixedGenerationalIndex + Send + Sync + 'a,
+{
+    fn drive<C>(self, consumer: C) -> C::Result
+    where
+        C: Consumer<Self::Item>,
+    {
+        bridge(self, consumer)
+    }
+
+    fn len(&self) -> usize {
+        self.live.len()
+    }
+
+    fn with_producer<CB>(self, callback: CB) -> CB::Output
+    where
+        CB: ProducerCallback<Self::Item>,
+    {
+        let focus = if self.live.is_empty() {
+            None
+        } else {
+            Some(self.focus)
+        };
+        callback.callback(BalancedProducer {
+            focus,
+            start: 0,
+            live: &self.live,
+        })
+    }
+}
+
+/// A sequential iterator over the elements whose slots are listed in `live`,
+/// which may skip some of the elements in the slots `inner` walks over.
+struct LiveIter<'a, 'b, T, I, G> {
+    start: usize,
+    live: core::slice::Iter<'b, usize>,
+    inner: Option<core::iter::Enumerate<ImIter<'a, Entry<T, I, G>>>>,
+}
+
+impl<'a, 'b, T, I, G> LiveIter<'a, 'b, T, I, G>
+where
+    T: Clone,
+    I: ArenaIndex,
+    G: FixedGenerationalIndex,
+{
+    fn element(slot: usize, entry: &'a Entry<T, I, G>) -> Element<'a, T, I, G> {
+        match entry {
+            Entry::Occupied { generation, value } => {
+                (Index::new(I::from_idx(slot), *generation), value)
+            }
+            Entry::Free { .. } | Entry::Retired => unreachable!(),
+        }
+    }
+}
+
+impl<'a, 'b, T, I, G> Iterator for LiveIter<'a, 'b, T, I, G>
+where
+    T: Clone,
+    I: ArenaIndex,
+    G: FixedGenerationalIndex,
+{
+    type Item = (Index<T, I, G>, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let slot = *self.live.next()?;
+        let inner = self.inner.as_mut()?;
+        loop {
+            let (i, entry) = inner.next()?;
+            if self.start + i == slot {
+                return Some(Self::element(slot, entry));
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.live.size_hint()
+    }
+}
+
+impl<'a, 'b, T, I, G> ExactSizeIterator for LiveIter<'a, 'b, T, I, G>
+where
+    T: Clone,
+    I: ArenaIndex,
+    G: FixedGenerationalIndex,
+{
+    fn len(&self) -> usize {
+        self.live.len()
+    }
+}
+
+impl<'a, 'b, T, I, G> DoubleEndedIterator for LiveIter<'a, 'b, T, I, G>
+where
+    T: Clone,
+    I: ArenaIndex,
+    G: FixedGenerationalIndex,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let slot = *self.live.next_back()?;
+        let inner = self.inner.as_mut()?;
+        loop {
+            let (i, entry) = inner.next_back()?;
+            if self.start + i == slot {
+                return Some(Self::element(slot, entry));
+            }
+        }
+    }
+}
+
+/// A producer over the slots `start..start + focus.len()`, holding the
+/// elements whose slots are listed in `live`. An empty producer has no
+/// focus, since `im` cannot split off an empty one.
+struct BalancedProducer<'a, 'b, T, I, G>
+where
+    T: Clone + Send + Sync,
+    I: ArenaIndex + Send + Sync,
+    G: FixedGenerationalIndex + Send + Sync,
+{
+    focus: Option<Focus<'a, Entry<T, I, G>>>,
+    start: usize,
+    live: &'b [usize],
+}
+
+impl<'a, 'b, T, I, G> Producer for BalancedProducer<'a, 'b, T, I, G>
+where
+    T: Clone + Send + Sync + 'a,
+    I: ArenaIndex + Send + Sync + 'a,
+    G: FixedGenerationalIndex + Send + Sync + 'a,
+{
+    type Item = (Index<T, I, G>, &'a T);
+    type IntoIter = LiveIter<'a, 'b, T, I, G>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        LiveIter {
+            start: self.start,
+            live: self.live.iter(),
+            inner: self.focus.map(|focus| focus.into_iter().enumerate()),
+        }
+    }
+
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let (left_live, right_live) = self.live.split_at(index);
+        // A half which keeps the whole focus also keeps its start, so slots
+        // are still counted from the beginning of the focus.
+        let (left, right, right_start) = match self.focus {
+            None => (None, None, self.start),
+            Some(focus) if left_live.is_empty() => (None, Some(focus), self.start),
+            Some(focus) if right_live.is_empty() => (Some(focus), None, self.start),
+            // Split just before the first element of the right half.
+            Some(focus) => {
+                let (left, right) = focus.split_at(right_live[0] - self.start);
+                (Some(left), Some(right), right_live[0])
+            }
+        };
+        (
+            BalancedProducer {
+                focus: left,
+                start: self.start,
+                live: left_live,
+            },
+            BalancedProducer {
+                focus: right,
+                start: right_start,
+                live: right_live,
+            },
+        )
+    }
+}
+
+impl<T, I, G> Arena<T, I, G>
+where
+    T: Clone + Send + Sync,
+    I: ArenaIndex + Send + Sync,
+    G: FixedGenerationalIndex + Send + Sync,
+{
+    /// Iterate in parallel over shared references to the elements in this
+    /// arena, splitting the work evenly by the number of elements.
+    ///
+    /// [`par_iter`](#method.par_iter) also splits by elements, but finds the
+    /// slot to split at by scanning from a count of the elements in every
+    /// chunk of slots. This iterator instead records the slot of every
+    /// element up front, allocating one `usize` per element, so every split
+    /// is exact and takes constant time, which pays off when rayon splits
+    /// the work very finely.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate rayon;
+    /// # extern crate generational_arena_im;
+    /// use generational_arena_im::StandardArena;
+    /// use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+    ///
+    /// let mut arena = StandardArena::new();
+    /// for i in 0..10 {
+    ///     arena.insert(i);
+    /// }
+    /// assert_eq!(arena.par_iter_balanced().len(), 10);
+    /// let sum: usize = arena.par_iter_balanced().map(|(_, v)| *v).sum();
+    /// assert_eq!(sum, 45);
+    /// ```
+    pub fn par_iter_balanced(&self) -> BalancedParIter<'_, T, I, G> {
+        let live = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| matches!(entry, Entry::Occupied { .. }))
+            .map(|(slot, _)| slot)
+            .collect();
+        BalancedParIter {
+            focus: self.items.focus(),
+            live,
+        }
+    }
+
+    /// Iterate in parallel over exclusive references to the elements in this
+    /// arena, paired with their raw slot number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate rayon;
+    /// # extern crate generational_arena_im;
+    /// use generational_arena_im::StandardArena;
+    /// use rayon::iter::ParallelIterator;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let idx = arena.insert(0);
+    /// arena.par_iter_mut_usize().for_each(|(slot, v)| *v = slot + 1);
+    /// assert_eq!(arena[idx], 1);
+    /// ```
+    pub fn par_iter_mut_usize(&mut self) -> impl ParallelIterator<Item = (usize, &mut T)> {
+        self.into_par_iter()
+            .map(|(idx, value)| (idx.to_idx(), value))
+    }
+
+    /// Call `f` in parallel on every element, along with its slot and the
+    /// entry of `field` at that slot.
+    ///
+    /// This suits updates that read from a separate array indexed by slot,
+    /// such as a force field in a physics step.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `field` is shorter than the arena's capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::with_capacity(2);
+    /// let a = arena.insert(1);
+    /// let b = arena.insert(2);
+    ///
+    /// arena.par_update_with(&[10, 20], |_, v, delta| *v += delta);
+    /// assert_eq!((arena[a], arena[b]), (11, 22));
+    /// ```
+    pub fn par_update_with<X, F>(&mut self, field: &[X], f: F)
+    where
+        X: Sync,
+        F: Fn(usize, &mut T, &X) + Sync,
+    {
+        assert!(
+            field.len() >= self.capacity(),
+            "field is shorter than the arena's capacity"
+        );
+        self.into_par_iter().for_each(|(idx, value)| {
+            let slot = idx.to_idx();
+            f(slot, value, &field[slot])
+        });
+    }
+
+    /// Iterate in parallel over the elements that satisfy `pred`, splitting
+    /// the work evenly by the number of those elements.
+    ///
+    /// Filtering after [`par_iter`](#method.par_iter) splits by all the
+    /// elements, so when `pred` rejects most of them the survivors can end
+    /// up bunched into a few of the splits. This instead evaluates `pred` on
+    /// every element in parallel first, recording the slot of each survivor,
+    /// then produces the survivors like
+    /// [`par_iter_balanced`](#method.par_iter_balanced), so each split holds
+    /// the same number of them. `pred` runs exactly once per element, and the
+    /// recorded slots take one `usize` per survivor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate rayon;
+    /// # extern crate generational_arena_im;
+    /// use generational_arena_im::StandardArena;
+    /// use rayon::iter::ParallelIterator;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// for i in 0..100 {
+    ///     arena.insert(i);
+    /// }
+    /// let sum: usize = arena
+    ///     .par_iter_filtered(|v| v % 10 == 0)
+    ///     .map(|(_, v)| *v)
+    ///     .sum();
+    /// assert_eq!(sum, 450);
+    /// ```
+    pub fn par_iter_filtered<F>(&self, pred: F) -> BalancedParIter<'_, T, I, G>
+    where
+        F: Fn(&T) -> bool + Sync,
+    {
+        let live = self
+            .par_iter()
+            .filter(|(_, value)| pred(value))
+            .map(|(idx, _)| idx.to_idx())
+            .collect();
+        BalancedParIter {
+            focus: self.items.focus(),
+            live,
+        }
+    }
+
+    /// Zip the elements of this arena with those of `other` in parallel,
+    /// pairing them up in slot order.
+    ///
+    /// This first collects the elements of both arenas, so the zipped
+    /// iterator splits evenly however the free slots of the two arenas are
+    /// laid out. Like `Iterator::zip`, it stops at the end of the shorter
+    /// arena.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate rayon;
+    /// # extern crate generational_arena_im;
+    /// use generational_arena_im::StandardArena;
+    /// use rayon::iter::ParallelIterator;
+    ///
+    /// let mut a = StandardArena::new();
+    /// let mut b = StandardArena::new();
+    /// let first = a.insert(0);
+    /// for i in 1..4 {
+    ///     a.insert(i);
+    ///     b.insert(i * 10);
+    /// }
+    /// a.remove(first);
+    ///
+    /// let products: Vec<_> = a
+    ///     .par_zip_live(&b)
+    ///     .map(|((_, x), (_, y))| x * y)
+    ///     .collect();
+    /// assert_eq!(products, vec![10, 40, 90]);
+    /// ```
+    pub fn par_zip_live<'a, U>(
+        &'a self,
+        other: &'a Arena<U, I, G>,
+    ) -> impl IndexedParallelIterator<Item = (Element<'a, T, I, G>, Element<'a, U, I, G>)> + 'a
+    where
+        U: Clone + Send + Sync,
+    {
+        let left: Vec<_> = self.iter().collect();
+        let right: Vec<_> = other.iter().collect();
+        left.into_par_iter().zip(right)
+    }
+
+    /// Construct a new arena by applying `f` to every element in parallel.
+    ///
+    /// The new arena has the same slots, generations and free list as this
+    /// one, so every index into this arena refers to the mapped element in
+    /// the new arena once its element type is changed with
+    /// [`Index::from_raw_untyped`](struct.Index.html#method.from_raw_untyped).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::{Index, StandardArena};
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let a = arena.insert(1);
+    /// let b = arena.insert(2);
+    /// arena.remove(a);
+    ///
+    /// let strings = arena.par_map(|v| v.to_string());
+    /// assert_eq!(strings[Index::from_raw_untyped(b.into_raw_untyped())], "2");
+    /// assert_eq!(strings.len(), 1);
+    /// ```
+    pub fn par_map<U, F>(&self, f: F) -> Arena<U, I, G>
+    where
+        U: Clone + Send + Sync,
+        F: Fn(&T) -> U + Sync + Send,
+    {
+        let items: Vec<_> = self
+            .items
+            .par_iter()
+            .map(|entry| match entry {
+                Entry::Occupied { generation, value } => Entry::Occupied {
+                    generation: *generation,
+                    value: f(value),
+                },
+                Entry::Free { next_free } => Entry::Free {
+                    next_free: *next_free,
+                },
+                Entry::Retired => Entry::Retired,
+            })
+            .collect();
+        // Unlike the constructors this copies every setting, so each field is
+        // listed to make adding one a compile error here.
+        Arena {
+            items: items.into(),
+            generation: self.generation,
+            len: self.len,
+            free_list_head: self.free_list_head,
+            max_len: self.max_len,
+            free_policy: self.free_policy,
+            growth: self.growth,
+            free_list_tail: self.free_list_tail,
+            no_grow: self.no_grow,
+            sealed: self.sealed,
+            version: self.version,
+            removals: self.removals,
+            deferred_removal: self.deferred_removal,
+            pending_removals: self.pending_removals.clone(),
+        }
+    }
+
+    /// Iterate in parallel over the indices of the elements in this arena.
+    ///
+    /// No element is borrowed by the iterator's items, which suits work
+    /// where each task looks up its own data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate rayon;
+    /// # extern crate generational_arena_im;
+    /// use generational_arena_im::StandardArena;
+    /// use rayon::iter::ParallelIterator;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let idx = arena.insert("a");
+    ///
+    /// let indices: Vec<_> = arena.par_indices().collect();
+    /// assert_eq!(indices, vec![idx]);
+    /// ```
+    pub fn par_indices(&self) -> impl ParallelIterator<Item = Index<T, I, G>> + '_ {
+        self.into_par_iter().map(|(idx, _)| idx)
+    }
+
+    /// Compute the elements of `values` in parallel and insert them into
+    /// consecutive slots at the end of the arena, in source order.
+    ///
+    /// Returns their indices in the same order as `values`, however the
+    /// computation was split between threads. Placement works as in
+    /// [`insert_contiguous`](#method.insert_contiguous), so free slots are
+    /// not reused.
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as
+    /// [`insert_contiguous`](#method.insert_contiguous).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate rayon;
+    /// # extern crate generational_arena_im;
+    /// use generational_arena_im::StandardArena;
+    /// use rayon::iter::{IntoParallelIterator, ParallelIterator};
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let indices = arena.par_insert_ordered((0..4).into_par_iter().map(|i| i * i));
+    ///
+    /// let values: Vec<_> = indices.iter().map(|&idx| arena[idx]).collect();
+    /// assert_eq!(values, vec![0, 1, 4, 9]);
+    /// ```
+    pub fn par_insert_ordered<P>(&mut self, values: P) -> Vec<Index<T, I, G>>
+    where
+        P: IndexedParallelIterator<Item = T>,
+    {
+        let mut computed = Vec::with_capacity(values.len());
+        values.collect_into_vec(&mut computed);
+        self.insert_contiguous(computed.into_iter())
+    }
+
+    /// Iterate in parallel over clones of the elements in this arena.
+    ///
+    /// Each element is cloned as it is produced, so later stages of the
+    /// pipeline own their data instead of borrowing from the arena.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate rayon;
+    /// # extern crate generational_arena_im;
+    /// use generational_arena_im::StandardArena;
+    /// use rayon::iter::ParallelIterator;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let idx = arena.insert(String::from("a"));
+    ///
+    /// let owned: Vec<_> = arena.par_iter_cloned().collect();
+    /// assert_eq!(owned, vec![(idx, String::from("a"))]);
+    /// ```
+    pub fn par_iter_cloned(&self) -> impl ParallelIterator<Item = (Index<T, I, G>, T)> + '_ {
+        self.items
+            .par_iter()
+            .enumerate()
+            .filter_map(|(slot, entry)| match entry {
+                Entry::Occupied { generation, value } => {
+                    Some((Index::new(I::from_idx(slot), *generation), value.clone()))
+                }
+                Entry::Free { .. } | Entry::Retired => None,
+            })
+    }
+
+    /// Count the elements for which `predicate` returns `true`, in
+    /// parallel.
+    ///
+    /// Only elements are tested, never free slots.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// for i in 0..10 {
+    ///     arena.insert(i);
+    /// }
+    ///
+    /// assert_eq!(arena.par_count(|v| v % 2 == 0), 5);
+    /// ```
+    pub fn par_count<F>(&self, predicate: F) -> usize
+    where
+        F: Fn(&T) -> bool + Sync + Send,
+    {
+        self.par_iter()
+            .filter(|(_, value)| predicate(value))
+            .count()
+    }
+
+    /// Call `f` for every element in parallel, threading per-worker state
+    /// through the calls.
+    ///
+    /// This wraps rayon's `for_each_with`: `init` is cloned whenever the work
+    /// is split, so each clone is only ever used by one thread at a time.
+    /// That makes it a good fit for things like random number generators or
+    /// reusable scratch buffers which would otherwise need locking or
+    /// reallocating for every element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    /// use std::sync::mpsc::channel;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// for i in 0..10 {
+    ///     arena.insert(i);
+    /// }
+    ///
+    /// let (sender, receiver) = channel();
+    /// arena.par_for_each_with(sender, |sender, (_, v)| sender.send(*v).unwrap());
+    /// assert_eq!(receiver.iter().sum::<usize>(), 45);
+    /// ```
+    pub fn par_for_each_with<S, F>(&self, init: S, f: F)
+    where
+        S: Clone + Send,
+        F: Fn(&mut S, (Index<T, I, G>, &T)) + Sync + Send,
+    {
+        self.par_iter().for_each_with(init, f)
+    }
+
+    /// Compute `f` for every element in parallel and collect the results
+    /// into a `BTreeMap` keyed by index.
+    ///
+    /// Each thread folds its share of the elements into its own map, and the
+    /// maps are then merged. Since the keys are unique and ordered, the
+    /// result does not depend on how the work was split.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let a = arena.insert(2);
+    /// let b = arena.insert(3);
+    ///
+    /// let squares = arena.par_collect_map(|v| v * v);
+    /// assert_eq!(squares[&a], 4);
+    /// assert_eq!(squares[&b], 9);
+    /// ```
+    pub fn par_collect_map<R, F>(&self, f: F) -> BTreeMap<Index<T, I, G>, R>
+    where
+        I: Ord,
+        R: Send,
+        F: Fn(&T) -> R + Sync,
+    {
+        self.items
+            .par_iter()
+            .enumerate()
+            .fold(BTreeMap::new, |mut map, (slot, entry)| {
+                if let Entry::Occupied { generation, value } = entry {
+                    map.insert(Index::new(I::from_idx(slot), *generation), f(value));
+                }
+                map
+            })
+            .reduce(BTreeMap::new, |mut left, mut right| {
+                if left.len() < right.len() {
+                    mem::swap(&mut left, &mut right);
+                }
+                left.append(&mut right);
+                left
+            })
+    }
+
+    /// Compute `f` for every element in parallel, writing each result into
+    /// `out` at the element's slot.
+    ///
+    /// Slots that are free are set to `None`, so the layout of `out` mirrors
+    /// the layout of the arena regardless of how the work was split.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` is shorter than the arena's capacity.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::with_capacity(3);
+    /// let a = arena.insert(1);
+    /// arena.insert(2);
+    /// arena.remove(a);
+    ///
+    /// let mut out = vec![None; arena.capacity()];
+    /// arena.par_scatter(&mut out, |_, v| v * 10);
+    /// assert_eq!(out, vec![None, Some(20), None]);
+    /// ```
+    pub fn par_scatter<R, F>(&self, out: &mut [Option<R>], f: F)
+    where
+        R: Send,
+        F: Fn(Index<T, I, G>, &T) -> R + Sync,
+    {
+        assert!(
+            out.len() >= self.items.len(),
+            "output buffer is shorter than the arena's capacity"
+        );
+        self.items
+            .par_iter()
+            .zip(out.par_iter_mut())
+            .enumerate()
+            .for_each(|(slot, (entry, out))| {
+                *out = match entry {
+                    Entry::Occupied { generation, value } => {
+                        Some(f(Index::new(I::from_idx(slot), *generation), value))
+                    }
+                    Entry::Free { .. } | Entry::Retired => None,
+                };
+            });
+    }
+}
+
+impl<T, I> Arena<T, I, DisableRemoval>
+where
+    T: Clone + Send + Sync,
+    I: ArenaIndex + Send + Sync,
+{
+    /// Iterate in parallel over the elements of a slab, in slot order.
+    ///
+    /// Slabs cannot remove elements, so their elements usually fill the
+    /// slots `0..len()` with only free capacity after them. In that case the
+    /// elements are split across threads directly, without checking each
+    /// slot for occupancy. A slab with free slots in between its elements,
+    /// as left by `reserve` or `from_slots`, falls back to
+    /// [`par_iter`](#method.par_iter).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate rayon;
+    /// # extern crate generational_arena_im;
+    /// use generational_arena_im::StandardSlab;
+    /// use rayon::iter::ParallelIterator;
+    ///
+    /// let mut slab = StandardSlab::new();
+    /// for i in 0..10 {
+    ///     slab.insert(i);
+    /// }
+    /// let sum: usize = slab.par_iter_dense().map(|(_, v)| *v).sum();
+    /// assert_eq!(sum, 45);
+    /// ```
+    pub fn par_iter_dense(
+        &self,
+    ) -> impl IndexedParallelIterator<Item = (Index<T, I, DisableRemoval>, &T)> {
+        if self.is_dense() {
+            // `take` splits the underlying producer at `len`, which `im`
+            // does not allow when that is the whole vector.
+            let occupied = if self.len == self.items.len() {
+                Either::Left(self.items.par_iter())
+            } else {
+                Either::Right(self.items.par_iter().take(self.len))
+            };
+            Either::Left(occupied.enumerate().map(|(slot, entry)| match entry {
+                Entry::Occupied { generation, value } => {
+                    (Index::new(I::from_idx(slot), *generation), value)
+                }
+                Entry::Free { .. } | Entry::Retired => unreachable!("dense slab has a free slot"),
+            }))
+        } else {
+            Either::Right(self.par_iter())
+        }
+    }
+
+    /// Do the elements fill exactly the slots `0..len()`?
+    fn is_dense(&self) -> bool {
+        // There are `len` occupied slots, so they are all below `len`
+        // exactly when every slot from `len` onwards is free.
+        self.all_free_from(self.len)
+    }
+}
@@ -0,0 +1,86 @@
+use crate::error::DecodeError;
+use core::num::Wrapping;
+
+cfg_if! {
+    if #[cfg(feature = "std")] {
+        use std::vec::Vec;
+    } else {
+        use alloc::vec::Vec;
+    }
+}
+
+/// A type which can be written in the compact binary format of
+/// [`Arena::encode`](struct.Arena.html#method.encode).
+pub trait Encode {
+    /// Append the encoding of `self` to `out`.
+    fn encode(&self, out: &mut Vec<u8>);
+}
+
+/// A type which can be read from the compact binary format of
+/// [`Arena::decode`](struct.Arena.html#method.decode).
+pub trait Decode: Sized {
+    /// Decode a value from the start of `input`, advancing `input` past it.
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError>;
+}
+
+/// Split `n` bytes off the start of `input`.
+pub(crate) fn take<'a>(input: &mut &'a [u8], n: usize) -> Result<&'a [u8], DecodeError> {
+    if input.len() < n {
+        return Err(DecodeError::UnexpectedEnd);
+    }
+    let (taken, rest) = input.split_at(n);
+    *input = rest;
+    Ok(taken)
+}
+
+macro_rules! impl_codec_for_int {
+    ($($ty:ty),*) => {
+        $(
+            impl Encode for $ty {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_le_bytes());
+                }
+            }
+
+            impl Decode for $ty {
+                fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+                    let mut bytes = [0; core::mem::size_of::<$ty>()];
+                    bytes.copy_from_slice(take(input, core::mem::size_of::<$ty>())?);
+                    Ok(<$ty>::from_le_bytes(bytes))
+                }
+            }
+        )*
+    };
+}
+
+impl_codec_for_int!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+// `usize` is always encoded as 64 bits, so that encodings are portable
+// between platforms.
+impl Encode for usize {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u64).encode(out);
+    }
+}
+
+impl Decode for usize {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        let value = u64::decode(input)?;
+        if value > usize::MAX as u64 {
+            return Err(DecodeError::InvalidValue);
+        }
+        Ok(value as usize)
+    }
+}
+
+impl<T: Encode> Encode for Wrapping<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+    }
+}
+
+impl<T: Decode> Decode for Wrapping<T> {
+    fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+        T::decode(input).map(Wrapping)
+    }
+}
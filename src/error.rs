@@ -0,0 +1,127 @@
+use core::fmt;
+
+/// The error returned when an arena cannot grow by the requested amount,
+/// either because the capacity would overflow `usize` or because the new
+/// slots would not be addressable by the arena's index type.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct CapacityOverflow;
+
+impl fmt::Display for CapacityOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("arena capacity overflow")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CapacityOverflow {}
+
+/// The error returned when removing an element from an arena whose
+/// generation type does not support removal, such as a slab using
+/// `DisableRemoval`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct RemovalUnsupported;
+
+impl fmt::Display for RemovalUnsupported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("this arena does not support removing elements")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RemovalUnsupported {}
+
+/// The reason a lookup by index found no element.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum LookupError {
+    /// The index's slot is beyond the arena's capacity, so the index did not
+    /// come from this arena.
+    OutOfBounds,
+    /// The index's element has been removed, and its slot is free or holds a
+    /// newer element.
+    Stale,
+}
+
+impl fmt::Display for LookupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LookupError::OutOfBounds => f.write_str("index out of bounds"),
+            LookupError::Stale => f.write_str("index is stale"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LookupError {}
+
+/// The error returned when a batch of indices cannot all be borrowed
+/// mutably at once.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DisjointError {
+    /// One of the indices found no element.
+    Lookup(LookupError),
+    /// Two of the indices refer to the same element.
+    Overlapping,
+}
+
+impl fmt::Display for DisjointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DisjointError::Lookup(e) => write!(f, "{}", e),
+            DisjointError::Overlapping => f.write_str("indices refer to the same element"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DisjointError {}
+
+/// The error returned when an element cannot be moved to another slot.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RelocateError {
+    /// The index of the element to move found no element.
+    Lookup(LookupError),
+    /// The target slot is occupied, retired, awaiting a deferred removal, or
+    /// beyond the arena's capacity.
+    TargetNotFree,
+}
+
+impl fmt::Display for RelocateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RelocateError::Lookup(e) => write!(f, "{}", e),
+            RelocateError::TargetNotFree => f.write_str("target slot is not free"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RelocateError {}
+
+/// The error returned when decoding an arena from bytes which are not a valid
+/// encoding of one.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DecodeError {
+    /// The input ended in the middle of a value.
+    UnexpectedEnd,
+    /// A slot's tag byte was neither free nor occupied.
+    InvalidTag(u8),
+    /// A value was out of range for its type, or the header disagrees with
+    /// the slots that follow it.
+    InvalidValue,
+    /// The input continued after the end of the arena.
+    TrailingBytes,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnexpectedEnd => f.write_str("unexpected end of input"),
+            DecodeError::InvalidTag(tag) => write!(f, "invalid slot tag {}", tag),
+            DecodeError::InvalidValue => f.write_str("invalid value in input"),
+            DecodeError::TrailingBytes => f.write_str("trailing bytes after arena"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
@@ -1,7 +1,21 @@
+use crate::encode::{Decode, Encode};
+use crate::error::{DecodeError, RemovalUnsupported};
+use core::cmp::Ordering;
 use core::default::Default;
+use core::num::Wrapping;
 use core::ops::{Add, AddAssign};
+#[cfg(target_has_atomic = "64")]
+use core::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
 use nonzero_ext::{NonZero, NonZeroAble};
-use num_traits::{One, ToPrimitive, WrappingAdd, Zero};
+use num_traits::{Bounded, CheckedAdd, One, ToPrimitive, WrappingAdd, Zero};
+
+cfg_if! {
+    if #[cfg(feature = "std")] {
+        use std::vec::Vec;
+    } else {
+        use alloc::vec::Vec;
+    }
+}
 
 /// A type which can be used as the index of a generation which may not be able to be incremented
 pub trait FixedGenerationalIndex: Copy + Eq {
@@ -9,16 +23,40 @@ pub trait FixedGenerationalIndex: Copy + Eq {
     fn first_generation() -> Self;
     /// Compare this generation with another.
     fn generation_lt(&self, other: &Self) -> bool;
+    /// Compare this generation with another, or return `None` if the two cannot be ordered, for
+    /// example because a wrapping counter may have wrapped between them. The default
+    /// implementation defers to `generation_lt` and always gives an ordering.
+    fn generation_partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.generation_lt(other) {
+            Some(Ordering::Less)
+        } else if self == other {
+            Some(Ordering::Equal)
+        } else {
+            Some(Ordering::Greater)
+        }
+    }
+    /// Advance this generation to record the removal of an element, or return
+    /// `Err(RemovalUnsupported)` if arenas using this generation type cannot remove elements.
+    /// Types which also implement `GenerationalIndex` must support removal, usually by calling
+    /// `increment_generation`.
+    fn increment_for_removal(&mut self) -> Result<(), RemovalUnsupported>;
 }
 
 /// A type which can be used as the index of a generation, which can be incremented
 pub trait GenerationalIndex: FixedGenerationalIndex {
     /// Increment the generation of this object. May wrap or panic on overflow depending on type.
     fn increment_generation(&mut self);
+    /// Increment the generation of this object unless that would overflow, returning whether it
+    /// was incremented. Types which wrap always increment. The default implementation defers to
+    /// `increment_generation`.
+    fn checked_increment_generation(&mut self) -> bool {
+        self.increment_generation();
+        true
+    }
 }
 
 /// A generation counter which is always nonzero. Useful for size optimizations on Option<Index>
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct NonzeroGeneration<T: NonZeroAble> {
     gen: T::NonZero,
 }
@@ -42,11 +80,26 @@ where
     }
 }
 
+impl<T> ToPrimitive for NonzeroGeneration<T>
+where
+    T: NonZeroAble,
+    T::NonZero: Copy,
+    <T::NonZero as NonZero>::Primitive: ToPrimitive,
+{
+    fn to_i64(&self) -> Option<i64> {
+        self.gen.get().to_i64()
+    }
+    fn to_u64(&self) -> Option<u64> {
+        self.gen.get().to_u64()
+    }
+}
+
 impl<T> FixedGenerationalIndex for NonzeroGeneration<T>
 where
     T: NonZeroAble
         + One
         + Add<Output = T>
+        + CheckedAdd
         + Copy
         + Eq
         + From<<<T as NonZeroAble>::NonZero as NonZero>::Primitive>,
@@ -62,6 +115,11 @@ where
     fn generation_lt(&self, other: &Self) -> bool {
         self.gen < other.gen
     }
+    #[inline(always)]
+    fn increment_for_removal(&mut self) -> Result<(), RemovalUnsupported> {
+        self.increment_generation();
+        Ok(())
+    }
 }
 
 impl<T> GenerationalIndex for NonzeroGeneration<T>
@@ -69,6 +127,7 @@ where
     T: NonZeroAble
         + One
         + Add<Output = T>
+        + CheckedAdd
         + Copy
         + Eq
         + From<<<T as NonZeroAble>::NonZero as NonZero>::Primitive>,
@@ -78,11 +137,24 @@ where
     fn increment_generation(&mut self) {
         self.gen = (T::from(self.gen.get()) + T::one()).into_nonzero().unwrap()
     }
+    #[inline(always)]
+    fn checked_increment_generation(&mut self) -> bool {
+        match T::from(self.gen.get())
+            .checked_add(&T::one())
+            .and_then(NonZeroAble::into_nonzero)
+        {
+            Some(gen) => {
+                self.gen = gen;
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 /// A wrapping generation counter which is always nonzero.
 /// Useful for size optimizations on Option<Index>
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct NonzeroWrapGeneration<T: NonZeroAble> {
     gen: T::NonZero,
 }
@@ -106,6 +178,20 @@ where
     }
 }
 
+impl<T> ToPrimitive for NonzeroWrapGeneration<T>
+where
+    T: NonZeroAble,
+    T::NonZero: Copy,
+    <T::NonZero as NonZero>::Primitive: ToPrimitive,
+{
+    fn to_i64(&self) -> Option<i64> {
+        self.gen.get().to_i64()
+    }
+    fn to_u64(&self) -> Option<u64> {
+        self.gen.get().to_u64()
+    }
+}
+
 impl<T> FixedGenerationalIndex for NonzeroWrapGeneration<T>
 where
     T: NonZeroAble
@@ -114,8 +200,11 @@ where
         + Copy
         + Eq
         + WrappingAdd
+        + Bounded
+        + ToPrimitive
         + From<<<T as NonZeroAble>::NonZero as NonZero>::Primitive>,
     T::NonZero: PartialOrd + Eq + Copy,
+    <T::NonZero as NonZero>::Primitive: ToPrimitive,
 {
     #[inline(always)]
     fn first_generation() -> Self {
@@ -127,6 +216,23 @@ where
     fn generation_lt(&self, other: &Self) -> bool {
         self.gen < other.gen
     }
+    /// Generations less than half the counter's range apart are ordered as usual. Generations
+    /// further apart than that may be on opposite sides of a wrap, so they are not ordered.
+    fn generation_partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let a = T::from(self.gen.get()).to_u64()?;
+        let b = T::from(other.gen.get()).to_u64()?;
+        let half_range = T::max_value().to_u64()? / 2;
+        if a.max(b) - a.min(b) < half_range {
+            Some(a.cmp(&b))
+        } else {
+            None
+        }
+    }
+    #[inline(always)]
+    fn increment_for_removal(&mut self) -> Result<(), RemovalUnsupported> {
+        self.increment_generation();
+        Ok(())
+    }
 }
 
 impl<T> GenerationalIndex for NonzeroWrapGeneration<T>
@@ -137,6 +243,8 @@ where
         + Copy
         + Eq
         + WrappingAdd
+        + Bounded
+        + ToPrimitive
         + From<<<T as NonZeroAble>::NonZero as NonZero>::Primitive>,
     T::NonZero: PartialOrd + Eq + Copy,
     <T::NonZero as NonZero>::Primitive: ToPrimitive,
@@ -152,24 +260,154 @@ where
     }
 }
 
-impl<T: Eq + One + AddAssign + Default + PartialOrd + Copy> FixedGenerationalIndex for T {
+macro_rules! impl_generation_for_int {
+    ($($ty:ty),*) => {
+        $(
+            impl FixedGenerationalIndex for $ty {
+                #[inline(always)]
+                fn first_generation() -> Self {
+                    Default::default()
+                }
+                #[inline(always)]
+                fn generation_lt(&self, other: &Self) -> bool {
+                    self.lt(other)
+                }
+                #[inline(always)]
+                fn increment_for_removal(&mut self) -> Result<(), RemovalUnsupported> {
+                    self.increment_generation();
+                    Ok(())
+                }
+            }
+
+            impl GenerationalIndex for $ty {
+                #[inline(always)]
+                fn increment_generation(&mut self) {
+                    *self += 1
+                }
+            }
+        )*
+    };
+}
+
+impl_generation_for_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl<T> FixedGenerationalIndex for Wrapping<T>
+where
+    T: Copy + Eq + PartialOrd + Default + Bounded + ToPrimitive,
+    Wrapping<T>: One + AddAssign,
+{
     #[inline(always)]
     fn first_generation() -> Self {
-        Default::default()
+        Wrapping(T::default())
     }
     #[inline(always)]
     fn generation_lt(&self, other: &Self) -> bool {
-        self.lt(other)
+        self.0 < other.0
+    }
+    /// Generations less than half the counter's range apart are ordered as usual. Generations
+    /// further apart than that may be on opposite sides of a wrap, so they are not ordered.
+    fn generation_partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let a = self.0.to_i128()?;
+        let b = other.0.to_i128()?;
+        let half_range = (T::max_value().to_i128()? - T::min_value().to_i128()?) / 2;
+        if a.max(b) - a.min(b) < half_range {
+            Some(a.cmp(&b))
+        } else {
+            None
+        }
+    }
+    #[inline(always)]
+    fn increment_for_removal(&mut self) -> Result<(), RemovalUnsupported> {
+        self.increment_generation();
+        Ok(())
     }
 }
 
-impl<T: Eq + One + AddAssign + Default + PartialOrd + Copy> GenerationalIndex for T {
+impl<T> GenerationalIndex for Wrapping<T>
+where
+    T: Copy + Eq + PartialOrd + Default + Bounded + ToPrimitive,
+    Wrapping<T>: One + AddAssign,
+{
     #[inline(always)]
     fn increment_generation(&mut self) {
         *self += Self::one()
     }
 }
 
+/// The source of every `AtomicGeneration` after the first. Starts at one, since zero is reserved
+/// for `first_generation`.
+#[cfg(target_has_atomic = "64")]
+static NEXT_ATOMIC_GENERATION: AtomicU64 = AtomicU64::new(1);
+
+/// A generation drawn from a process-wide atomic counter, so that no two removals in any arena
+/// ever produce the same generation.
+///
+/// The value itself is a plain `u64` and is copied into every `Index` like any other generation;
+/// the interior mutability lives in the shared counter, which `increment_generation` advances
+/// with a single `fetch_add`. Minting a handle never touches the counter, since inserts reuse
+/// the arena's current generation, so threads inserting into their own arenas do not contend at
+/// all. Only removals draw from the counter, one wait-free increment each, and as every value is
+/// drawn once, a handle from one arena never matches an element of another.
+///
+/// The counter is only ever read and advanced through that read-modify-write, whose results are
+/// unique and increasing under any ordering, so it uses `Relaxed`: no other memory is published
+/// through it, and the arena's own slots are synchronized by `&mut self` or whatever lock wraps
+/// the arena.
+#[cfg(target_has_atomic = "64")]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct AtomicGeneration {
+    gen: u64,
+}
+
+#[cfg(target_has_atomic = "64")]
+impl ToPrimitive for AtomicGeneration {
+    fn to_i64(&self) -> Option<i64> {
+        self.gen.to_i64()
+    }
+    fn to_u64(&self) -> Option<u64> {
+        Some(self.gen)
+    }
+}
+
+#[cfg(target_has_atomic = "64")]
+impl FixedGenerationalIndex for AtomicGeneration {
+    #[inline(always)]
+    fn first_generation() -> Self {
+        AtomicGeneration { gen: 0 }
+    }
+    #[inline(always)]
+    fn generation_lt(&self, other: &Self) -> bool {
+        self.gen < other.gen
+    }
+    #[inline(always)]
+    fn increment_for_removal(&mut self) -> Result<(), RemovalUnsupported> {
+        self.increment_generation();
+        Ok(())
+    }
+}
+
+#[cfg(target_has_atomic = "64")]
+impl GenerationalIndex for AtomicGeneration {
+    /// Take the next value from the shared counter. Panics once the counter is exhausted.
+    #[inline]
+    fn increment_generation(&mut self) {
+        if !self.checked_increment_generation() {
+            panic!("Atomic generation counter overflowed")
+        }
+    }
+    #[inline]
+    fn checked_increment_generation(&mut self) -> bool {
+        // At a billion removals a second the counter lasts for centuries, so it is not worth a
+        // compare-and-swap loop to stop it wrapping; the removal that wraps it still fails.
+        let next = NEXT_ATOMIC_GENERATION.fetch_add(1, AtomicOrdering::Relaxed);
+        if next == u64::MAX {
+            return false;
+        }
+        self.gen = next;
+        true
+    }
+}
+
 /// If this is used as a generational index, then the arena ignores generation
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
 pub struct IgnoreGeneration;
@@ -183,6 +421,11 @@ impl FixedGenerationalIndex for IgnoreGeneration {
     fn generation_lt(&self, _other: &Self) -> bool {
         false
     }
+    #[inline(always)]
+    fn increment_for_removal(&mut self) -> Result<(), RemovalUnsupported> {
+        self.increment_generation();
+        Ok(())
+    }
 }
 
 impl GenerationalIndex for IgnoreGeneration {
@@ -208,6 +451,59 @@ impl FixedGenerationalIndex for DisableRemoval {
     fn generation_lt(&self, _other: &Self) -> bool {
         false
     }
+    #[inline(always)]
+    fn increment_for_removal(&mut self) -> Result<(), RemovalUnsupported> {
+        Err(RemovalUnsupported)
+    }
 }
 
 impl IgnoredGeneration for DisableRemoval {}
+
+macro_rules! impl_codec_for_nonzero_generation {
+    ($($gen:ident),*) => {
+        $(
+            impl<T> Encode for $gen<T>
+            where
+                T: NonZeroAble + Encode + From<<T::NonZero as NonZero>::Primitive>,
+                T::NonZero: Copy,
+            {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    T::from(self.gen.get()).encode(out);
+                }
+            }
+
+            impl<T> Decode for $gen<T>
+            where
+                T: NonZeroAble + Decode,
+            {
+                fn decode(input: &mut &[u8]) -> Result<Self, DecodeError> {
+                    let gen = T::decode(input)?
+                        .into_nonzero()
+                        .ok_or(DecodeError::InvalidValue)?;
+                    Ok($gen { gen })
+                }
+            }
+        )*
+    };
+}
+
+impl_codec_for_nonzero_generation!(NonzeroGeneration, NonzeroWrapGeneration);
+
+// Generations which carry no information take up no space.
+macro_rules! impl_codec_for_unit_generation {
+    ($($gen:ident),*) => {
+        $(
+            impl Encode for $gen {
+                fn encode(&self, _out: &mut Vec<u8>) {}
+            }
+
+            impl Decode for $gen {
+                fn decode(_input: &mut &[u8]) -> Result<Self, DecodeError> {
+                    Ok($gen)
+                }
+            }
+        )*
+    };
+}
+
+impl_codec_for_unit_generation!(DisableRemoval, IgnoreGeneration);
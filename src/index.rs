@@ -11,6 +11,10 @@ pub trait ArenaIndex: Copy {
     fn from_idx(idx: usize) -> Self;
     /// Transform an arena index into a usize
     fn to_idx(self) -> usize;
+    /// Create an arena index from a usize, or `None` if it is out of range
+    fn try_from_idx(idx: usize) -> Option<Self> {
+        Some(Self::from_idx(idx))
+    }
 }
 impl<T: ToPrimitive + FromPrimitive + Copy> ArenaIndex for T {
     #[inline(always)]
@@ -18,6 +22,10 @@ impl<T: ToPrimitive + FromPrimitive + Copy> ArenaIndex for T {
         Self::from_usize(idx).unwrap()
     }
     #[inline(always)]
+    fn try_from_idx(idx: usize) -> Option<Self> {
+        Self::from_usize(idx)
+    }
+    #[inline(always)]
     fn to_idx(self) -> usize {
         self.to_usize().unwrap()
     }
@@ -45,6 +53,42 @@ where
     fn to_idx(self) -> usize {
         self.idx.get().to_usize().unwrap() - 1
     }
+    #[inline(always)]
+    fn try_from_idx(idx: usize) -> Option<Self> {
+        let idx = T::from_usize(idx.checked_add(1)?)?.into_nonzero()?;
+        Some(NonZeroIndex { idx })
+    }
+}
+
+/// Serializes as the underlying nonzero value.
+#[cfg(feature = "serde")]
+impl<T> serde::Serialize for NonZeroIndex<T>
+where
+    T: NonZeroAble,
+    <<T as NonZeroAble>::NonZero as NonZero>::Primitive: serde::Serialize,
+    T::NonZero: Copy,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.idx.get().serialize(serializer)
+    }
+}
+
+/// Deserializes from the underlying nonzero value, rejecting zero.
+#[cfg(feature = "serde")]
+impl<'de, T> serde::Deserialize<'de> for NonZeroIndex<T>
+where
+    T: NonZeroAble,
+    <<T as NonZeroAble>::NonZero as NonZero>::Primitive: serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = serde::Deserialize::deserialize(deserializer)?;
+        match T::NonZero::new(raw) {
+            Some(idx) => Ok(NonZeroIndex { idx }),
+            None => Err(serde::de::Error::custom(
+                "expected a nonzero value for NonZeroIndex",
+            )),
+        }
+    }
 }
 
 /// An index (and generation) into an `Arena`.
@@ -161,13 +205,115 @@ impl<T, I: ArenaIndex, G: FixedGenerationalIndex> Index<T, I, G> {
     #[inline]
     pub fn new(index: I, generation: G) -> Index<T, I, G> {
         Index {
-            index: index,
-            generation: generation,
+            index,
+            generation,
             _phantom: std::marker::PhantomData,
         }
     }
 }
 
+impl<T, I, G: FixedGenerationalIndex> Index<T, I, G> {
+    /// Is this index from a newer generation than `other`?
+    ///
+    /// Only the generations are compared. Returns `None` if they cannot be
+    /// ordered, which happens for wrapping generation counters when the two
+    /// generations are far enough apart that the counter may have wrapped
+    /// between them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let old = arena.insert(1);
+    /// arena.remove(old);
+    /// let new = arena.insert(2);
+    ///
+    /// assert_eq!(new.newer_than(&old), Some(true));
+    /// assert_eq!(old.newer_than(&new), Some(false));
+    /// ```
+    pub fn newer_than(&self, other: &Self) -> Option<bool> {
+        self.generation
+            .generation_partial_cmp(&other.generation)
+            .map(|ordering| ordering == Ordering::Greater)
+    }
+}
+
+impl<T, I, G> Index<T, I, G> {
+    /// Erase the element type of this index, so that indices into arenas of
+    /// different element types can be stored together.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::StandardArena;
+    ///
+    /// let mut numbers = StandardArena::new();
+    /// let mut names = StandardArena::new();
+    /// let handles = vec![
+    ///     numbers.insert(1).into_raw_untyped(),
+    ///     names.insert("one").into_raw_untyped(),
+    /// ];
+    /// assert_eq!(handles.len(), 2);
+    /// ```
+    #[inline]
+    pub fn into_raw_untyped(self) -> RawIndex<I, G> {
+        RawIndex {
+            index: self.index,
+            generation: self.generation,
+        }
+    }
+
+    /// Give an untyped index an element type again.
+    ///
+    /// Choosing a different element type than the index was created with is
+    /// a logic error, but not undefined behavior: the index is still checked
+    /// against the generation of whatever slot it is used with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::{StandardArena, StandardIndex};
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let raw = arena.insert(42).into_raw_untyped();
+    ///
+    /// let idx: StandardIndex<i32> = StandardIndex::from_raw_untyped(raw);
+    /// assert_eq!(arena[idx], 42);
+    /// ```
+    #[inline]
+    pub fn from_raw_untyped(raw: RawIndex<I, G>) -> Self {
+        Index {
+            index: raw.index,
+            generation: raw.generation,
+            _phantom: core::marker::PhantomData,
+        }
+    }
+}
+
+/// An [`Index`](struct.Index.html) without an element type.
+///
+/// Created by [`Index::into_raw_untyped`](struct.Index.html#method.into_raw_untyped),
+/// and turned back into an `Index` by
+/// [`Index::from_raw_untyped`](struct.Index.html#method.from_raw_untyped).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct RawIndex<I = usize, G = u64> {
+    index: I,
+    generation: G,
+}
+
+impl<I: Copy, G: Copy> RawIndex<I, G> {
+    /// Get this index's array index into the arena
+    pub fn arr_idx(&self) -> I {
+        self.index
+    }
+    /// Get this index's generation
+    pub fn gen(&self) -> G {
+        self.generation
+    }
+}
+
 impl<T, I: PartialOrd, G: FixedGenerationalIndex> PartialOrd for Index<T, I, G> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         match self.index.partial_cmp(&other.index) {
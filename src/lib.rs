@@ -150,6 +150,15 @@ currently requires nightly Rust and `feature(alloc)` to get access to `Vec`.
 typed-generational-arena = { version = "0.2", default-features = false }
 ```
 
+## Serialization
+
+Enabling the "serde" feature implements `Serialize` and `Deserialize` for
+`NonZeroIndex`.
+
+Without serde, `Arena::encode` and `Arena::decode` write and read a compact
+binary format, for element types implementing this crate's `Encode` and
+`Decode` traits.
+
  */
 
 #![forbid(unsafe_code, missing_docs, missing_debug_implementations)]
@@ -162,6 +171,8 @@ extern crate num_traits;
 extern crate cfg_if;
 extern crate im;
 extern crate rayon;
+#[cfg(feature = "serde")]
+extern crate serde;
 
 cfg_if! {
     if #[cfg(feature = "std")] {
@@ -176,12 +187,26 @@ mod presets;
 pub use presets::*;
 
 mod arena;
+mod encode;
+mod error;
 mod generation;
 mod index;
+mod secondary;
 
-pub use arena::{Arena, Drain, IntoIter, Iter, IterMut};
+pub use arena::{
+    Arena, ArenaBuilder, ArenaOp, Borrows, Drain, DrainWhere, EntryKind, FreePolicy,
+    FrozenArena, GrowthStrategy, IntoIter, Iter, IterMut, OwnedArenaOp, SlotDelta, SlotState,
+    StateStamp,
+};
+pub use encode::{Decode, Encode};
+pub use error::{
+    CapacityOverflow, DecodeError, DisjointError, LookupError, RelocateError, RemovalUnsupported,
+};
+#[cfg(target_has_atomic = "64")]
+pub use generation::AtomicGeneration;
 pub use generation::{
     DisableRemoval, FixedGenerationalIndex, GenerationalIndex, IgnoreGeneration, IgnoredGeneration,
     NonzeroGeneration, NonzeroWrapGeneration,
 };
-pub use index::{ArenaIndex, Index, NonZeroIndex};
+pub use index::{ArenaIndex, Index, NonZeroIndex, RawIndex};
+pub use secondary::SecondaryMap;
@@ -1,4 +1,6 @@
-use super::{Arena, Index, NonzeroGeneration, NonzeroWrapGeneration, NonZeroIndex, DisableRemoval};
+#[cfg(target_has_atomic = "64")]
+use super::AtomicGeneration;
+use super::{Arena, Index, NonzeroGeneration, NonzeroWrapGeneration, NonZeroIndex, DisableRemoval, SecondaryMap};
 
 /// An arena of `T` indexed by `usize`, with `2^{64}` generations
 pub type U64Arena<T> = Arena<T, usize, u64>;
@@ -8,6 +10,8 @@ pub type U64Index<T> = Index<T, usize, u64>;
 pub type StandardArena<T> =  Arena<T, usize, NonzeroGeneration<usize>>;
 /// A typed index into a `StandardArena`
 pub type StandardIndex<T> = Index<T, usize, NonzeroGeneration<usize>>;
+/// A secondary map keyed by the indices of a `StandardArena`
+pub type StandardSecondaryMap<T> = SecondaryMap<T, usize, NonzeroGeneration<usize>>;
 /// An arena which can only hold up to \(2^{32} - 1\) elements and generations
 pub type SmallArena<T> = Arena<T, u32, NonzeroGeneration<u32>>;
 /// A typed index into a `StandardArena`
@@ -37,6 +41,13 @@ pub type NanoIndex<T> = Index<T, u8, core::num::Wrapping<u8>>;
 pub type PicoArena<T> = Arena<T, u8, NonzeroWrapGeneration<u8>>;
 /// A typed index into a `NanoArena`
 pub type PicoIndex<T> = Index<T, u8, NonzeroWrapGeneration<u8>>;
+/// An arena of `T` indexed by `usize` whose generations come from a process-wide atomic
+/// counter, so handles from different arenas and threads never share a generation
+#[cfg(target_has_atomic = "64")]
+pub type AtomicArena<T> = Arena<T, usize, AtomicGeneration>;
+/// A typed index into an `AtomicArena`
+#[cfg(target_has_atomic = "64")]
+pub type AtomicIndex<T> = Index<T, usize, AtomicGeneration>;
 /// A slab arena with a given index, which does *not* support efficient removal
 pub type Slab<T, I> = Arena<T, I, DisableRemoval>;
 /// An index into a slab of type `T` by a certain type
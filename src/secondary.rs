@@ -0,0 +1,211 @@
+use crate::arena::Entry;
+use crate::generation::FixedGenerationalIndex;
+use crate::index::{ArenaIndex, Index};
+use core::{mem, ops};
+use im::Vector;
+
+/// A map from the indices of a primary `Arena` to values of another type.
+///
+/// A `SecondaryMap` stores a `T` at the same slot as the primary element the
+/// key refers to, along with that key's generation, so lookups with a stale
+/// handle fail exactly as they would in the primary arena. It is populated
+/// independently of the primary arena: removing an element from the primary
+/// does not remove its entry here.
+///
+/// # Examples
+///
+/// ```
+/// use generational_arena_im::{StandardArena, StandardSecondaryMap};
+///
+/// let mut names = StandardArena::new();
+/// let mut ages = StandardSecondaryMap::new();
+///
+/// let alice = names.insert("Alice");
+/// ages.insert(alice, 30);
+/// assert_eq!(ages.get(alice), Some(&30));
+///
+/// names.remove(alice);
+/// let bob = names.insert("Bob");
+/// // `bob` reuses the slot `alice` had, but with a newer generation.
+/// assert_eq!(ages.get(bob), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SecondaryMap<T: Clone, I: Clone = usize, G: Clone = usize> {
+    items: Vector<Entry<T, I, G>>,
+    len: usize,
+}
+
+impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> SecondaryMap<T, I, G> {
+    /// Constructs a new, empty `SecondaryMap`.
+    pub fn new() -> SecondaryMap<T, I, G> {
+        SecondaryMap {
+            items: Vector::new(),
+            len: 0,
+        }
+    }
+
+    /// Insert `value` for the primary element at index `i`.
+    ///
+    /// If a value was already stored for `i`, it is replaced and returned.
+    /// A value stored for an older generation of the same slot is
+    /// overwritten and dropped. If a value is stored for a newer generation
+    /// of the slot, then `i` is stale: the map is left unchanged, and `value`
+    /// is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::{StandardArena, StandardSecondaryMap};
+    ///
+    /// let mut arena = StandardArena::new();
+    /// let mut map = StandardSecondaryMap::new();
+    /// let idx = arena.insert(());
+    ///
+    /// assert_eq!(map.insert(idx, 1), None);
+    /// assert_eq!(map.insert(idx, 2), Some(1));
+    ///
+    /// arena.remove(idx);
+    /// let newer = arena.insert(());
+    /// assert_eq!(map.insert(newer, 3), None);
+    /// assert_eq!(map.insert(idx, 4), None);
+    /// assert_eq!(map.get(newer), Some(&3));
+    /// ```
+    pub fn insert<P>(&mut self, i: Index<P, I, G>, value: T) -> Option<T> {
+        let slot = i.index.to_idx();
+        if let Some(Entry::Occupied { generation, .. }) = self.items.get(slot) {
+            if i.generation.generation_lt(generation) {
+                return None;
+            }
+        }
+        while self.items.len() <= slot {
+            self.items.push_back(Entry::Free { next_free: None });
+        }
+        let old = mem::replace(
+            &mut self.items[slot],
+            Entry::Occupied {
+                generation: i.generation,
+                value,
+            },
+        );
+        match old {
+            Entry::Occupied { generation, value } if generation == i.generation => Some(value),
+            Entry::Occupied { .. } => None,
+            Entry::Free { .. } | Entry::Retired => {
+                self.len += 1;
+                None
+            }
+        }
+    }
+
+    /// Get a shared reference to the value stored for index `i`, if any.
+    pub fn get<P>(&self, i: Index<P, I, G>) -> Option<&T> {
+        match self.items.get(i.index.to_idx()) {
+            Some(Entry::Occupied { generation, value }) if *generation == i.generation => {
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    /// Get an exclusive reference to the value stored for index `i`, if any.
+    pub fn get_mut<P>(&mut self, i: Index<P, I, G>) -> Option<&mut T> {
+        match self.items.get_mut(i.index.to_idx()) {
+            Some(Entry::Occupied { generation, value }) if *generation == i.generation => {
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    /// Is there a value stored for index `i`?
+    pub fn contains<P>(&self, i: Index<P, I, G>) -> bool {
+        self.get(i).is_some()
+    }
+
+    /// Remove the value stored for index `i`, returning it if it was present.
+    pub fn remove<P>(&mut self, i: Index<P, I, G>) -> Option<T> {
+        let slot = i.index.to_idx();
+        match self.items.get(slot) {
+            Some(Entry::Occupied { generation, .. }) if *generation == i.generation => {}
+            _ => return None,
+        }
+        match mem::replace(&mut self.items[slot], Entry::Free { next_free: None }) {
+            Entry::Occupied { value, .. } => {
+                self.len -= 1;
+                Some(value)
+            }
+            Entry::Free { .. } | Entry::Retired => unreachable!(),
+        }
+    }
+
+    /// Get the number of values stored in this map.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the map contains no values
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove every value from this map.
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.len = 0;
+    }
+
+    /// Iterate over the stored values in slot order.
+    ///
+    /// Yields pairs of `(Index<P>, &T)` items, where `P` is the value type of
+    /// the primary arena the keys came from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use generational_arena_im::{StandardArena, StandardSecondaryMap};
+    ///
+    /// let mut names = StandardArena::new();
+    /// let mut ages = StandardSecondaryMap::new();
+    /// let alice = names.insert("Alice");
+    /// ages.insert(alice, 30);
+    ///
+    /// for (idx, age) in ages.iter::<&str>() {
+    ///     println!("{} is {}", names[idx], age);
+    /// }
+    /// ```
+    pub fn iter<P>(&self) -> impl Iterator<Item = (Index<P, I, G>, &T)> + '_ {
+        self.items
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, entry)| match entry {
+                Entry::Occupied { generation, value } => {
+                    Some((Index::new(I::from_idx(slot), *generation), value))
+                }
+                Entry::Free { .. } | Entry::Retired => None,
+            })
+    }
+}
+
+impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex> Default for SecondaryMap<T, I, G> {
+    fn default() -> Self {
+        SecondaryMap::new()
+    }
+}
+
+impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex, P> ops::Index<Index<P, I, G>>
+    for SecondaryMap<T, I, G>
+{
+    type Output = T;
+
+    fn index(&self, index: Index<P, I, G>) -> &Self::Output {
+        self.get(index).expect("No element at index")
+    }
+}
+
+impl<T: Clone, I: ArenaIndex, G: FixedGenerationalIndex, P> ops::IndexMut<Index<P, I, G>>
+    for SecondaryMap<T, I, G>
+{
+    fn index_mut(&mut self, index: Index<P, I, G>) -> &mut Self::Output {
+        self.get_mut(index).expect("No element at index")
+    }
+}
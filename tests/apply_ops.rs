@@ -0,0 +1,64 @@
+extern crate generational_arena_im;
+
+use generational_arena_im::{OwnedArenaOp, StandardArena, StandardIndex};
+
+#[test]
+fn replaying_a_diff_reconstructs_the_arena() {
+    let mut arena = StandardArena::with_capacity(4);
+    let indices: Vec<_> = (0..4).map(|i| arena.insert(i)).collect();
+    let base = arena.clone();
+
+    arena[indices[0]] = 10;
+    arena.remove(indices[1]);
+    arena.remove(indices[2]);
+    let reused = arena.insert(20);
+    let grown: Vec<_> = (30..33).map(|i| arena.insert(i)).collect();
+
+    let log: Vec<_> = arena
+        .diff(&base)
+        .into_iter()
+        .map(|op| op.into_owned())
+        .collect();
+    let mut replica = base.clone();
+    assert_eq!(replica.apply_ops(log), 0);
+
+    assert_eq!(replica, arena);
+    assert_eq!(replica.len(), arena.len());
+    for (idx, value) in arena.iter() {
+        assert_eq!(replica.get(idx), Some(value));
+    }
+    assert_eq!(replica[reused], 20);
+    assert!(!replica.contains(indices[1]));
+    assert!(grown.iter().all(|idx| replica.contains(*idx)));
+
+    // The replica's free list still works afterwards.
+    let extra = replica.insert(99);
+    assert_eq!(replica[extra], 99);
+    assert!(arena.iter().all(|(idx, _)| idx != extra));
+}
+
+#[test]
+fn ops_that_do_not_fit_are_skipped() {
+    let mut arena = StandardArena::with_capacity(2);
+    let a = arena.insert(1);
+    let b = arena.insert(2);
+    arena.remove(b);
+
+    let skipped = arena.apply_ops(vec![
+        OwnedArenaOp::Remove(b),
+        OwnedArenaOp::Change(b, 5),
+        OwnedArenaOp::Insert(a.arr_idx(), a.gen(), 7),
+        OwnedArenaOp::Change(a, 3),
+    ]);
+    assert_eq!(skipped, 3);
+    assert_eq!(arena[a], 3);
+    assert_eq!(arena.len(), 1);
+
+    let far = StandardIndex::from_raw(6, a.gen());
+    assert_eq!(
+        arena.apply_ops(vec![OwnedArenaOp::Insert(6, a.gen(), 8)]),
+        0
+    );
+    assert_eq!(arena[far], 8);
+    assert_eq!(arena.capacity(), 7);
+}
@@ -0,0 +1,66 @@
+extern crate generational_arena_im;
+use generational_arena_im::{AtomicArena, AtomicIndex};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+#[test]
+fn atomic_arena_invalidates_removed_handles() {
+    let mut arena = AtomicArena::new();
+    let a = arena.insert("a");
+    let b = arena.insert("b");
+    assert_eq!(arena.remove(a), Some("a"));
+    assert!(!arena.contains(a));
+    assert_eq!(arena.get(b), Some(&"b"));
+
+    let c = arena.insert("c");
+    assert_eq!(c.arr_idx(), a.arr_idx());
+    assert!(a.gen() < c.gen());
+    assert_eq!(arena.get(a), None);
+    assert_eq!(arena.get(c), Some(&"c"));
+    assert_eq!(arena.remove(a), None);
+    assert_eq!(arena.len(), 2);
+}
+
+#[test]
+fn atomic_arenas_on_different_threads_never_share_a_generation() {
+    let arenas: Vec<_> = (0..4)
+        .map(|_| Arc::new(Mutex::new(AtomicArena::new())))
+        .collect();
+    let handles: Vec<_> = arenas
+        .iter()
+        .cloned()
+        .map(|arena| {
+            thread::spawn(move || {
+                let mut minted: Vec<AtomicIndex<usize>> = Vec::new();
+                for i in 0..100 {
+                    let mut arena = arena.lock().unwrap();
+                    let idx = arena.insert(i);
+                    assert_eq!(arena.remove(idx), Some(i));
+                    minted.push(arena.insert(i));
+                    arena.remove(*minted.last().unwrap());
+                }
+                minted
+            })
+        })
+        .collect();
+    let mut gens: Vec<_> = handles
+        .into_iter()
+        .flat_map(|handle| handle.join().unwrap())
+        .map(|idx| idx.gen())
+        .collect();
+    let minted = gens.len();
+    gens.sort();
+    gens.dedup();
+    assert_eq!(gens.len(), minted);
+
+    // A handle minted in one arena never validates against another arena's element in the
+    // same slot.
+    let mut first = arenas[0].lock().unwrap();
+    let mut second = arenas[1].lock().unwrap();
+    let a = first.insert(7);
+    let b = second.insert(8);
+    assert_eq!(a.to_idx(), b.to_idx());
+    assert_ne!(a.gen(), b.gen());
+    assert_eq!(second.get(a), None);
+    assert_eq!(first.get(b), None);
+}
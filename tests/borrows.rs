@@ -0,0 +1,53 @@
+extern crate generational_arena_im;
+use generational_arena_im::StandardArena as Arena;
+
+#[test]
+fn mutate_one_while_reading_neighbors() {
+    let mut arena = Arena::new();
+    let a = arena.insert(10);
+    let b = arena.insert(20);
+    let c = arena.insert(30);
+
+    let (b_mut, neighbors) = arena.borrows().mutable(b).shared(a).shared(c).resolve();
+    let b_mut = b_mut.unwrap();
+    *b_mut = neighbors.iter().map(|n| *n.unwrap()).sum();
+
+    assert_eq!(arena[a], 10);
+    assert_eq!(arena[b], 40);
+    assert_eq!(arena[c], 30);
+}
+
+#[test]
+fn stale_handles_resolve_to_none() {
+    let mut arena = Arena::new();
+    let a = arena.insert(1);
+    let b = arena.insert(2);
+    let c = arena.insert(3);
+    arena.remove(c);
+
+    let (a_mut, shared) = arena
+        .borrows()
+        .mutable(a)
+        .shared(c)
+        .shared(b)
+        .shared(b)
+        .resolve();
+    assert_eq!(a_mut, Some(&mut 1));
+    assert_eq!(shared, vec![None, Some(&2), Some(&2)]);
+
+    let (none, shared) = arena.borrows().mutable(c).shared(a).resolve();
+    assert!(none.is_none());
+    assert_eq!(shared, vec![Some(&1)]);
+
+    let (none, shared) = arena.borrows().shared(a).resolve();
+    assert!(none.is_none());
+    assert_eq!(shared, vec![Some(&1)]);
+}
+
+#[test]
+#[should_panic(expected = "aliases")]
+fn overlapping_mutable_and_shared_panics() {
+    let mut arena = Arena::new();
+    let a = arena.insert(1);
+    let _ = arena.borrows().mutable(a).shared(a).resolve();
+}
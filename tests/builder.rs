@@ -0,0 +1,120 @@
+extern crate generational_arena_im;
+
+use generational_arena_im::{ArenaBuilder, FreePolicy, GrowthStrategy, StandardArena};
+
+#[test]
+fn builder_applies_every_option() {
+    let mut arena: StandardArena<u32> = ArenaBuilder::new()
+        .capacity(6)
+        .free_policy(FreePolicy::Fifo)
+        .max_len(5)
+        .no_grow()
+        .build();
+    assert_eq!(arena.capacity(), 6);
+    assert_eq!(arena.max_len(), Some(5));
+
+    let indices: Vec<_> = (0..5).map(|i| arena.insert(i)).collect();
+    assert_eq!(arena.checked_insert(5), Err(5));
+
+    // Freed slots come back in the order they were freed, after the slot
+    // that was never used.
+    arena.remove(indices[3]);
+    arena.remove(indices[1]);
+    arena.remove(indices[4]);
+    let reused: Vec<_> = (0..3).map(|i| arena.insert(i).to_idx()).collect();
+    assert_eq!(reused, vec![5, 3, 1]);
+    assert_eq!(arena.capacity(), 6);
+}
+
+#[test]
+fn fifo_reuses_after_untouched_capacity() {
+    let mut arena: StandardArena<u32> = ArenaBuilder::new()
+        .capacity(4)
+        .free_policy(FreePolicy::Fifo)
+        .build();
+    let a = arena.insert(0);
+    arena.insert(1);
+    arena.remove(a);
+    // Slots 2 and 3 were free before slot 0 was.
+    let slots: Vec<_> = (0..3).map(|i| arena.insert(i).to_idx()).collect();
+    assert_eq!(slots, vec![2, 3, 0]);
+}
+
+#[test]
+fn fifo_keeps_the_free_list_tail_in_step() {
+    let mut arena: StandardArena<u32> = ArenaBuilder::new()
+        .capacity(2)
+        .free_policy(FreePolicy::Fifo)
+        .build();
+    let a = arena.insert(0);
+    arena.insert(1);
+    arena.remove(a);
+    // Slot 0 was the tail of the free list until this takes it back off.
+    let c = arena.insert(2);
+    assert_eq!(c.to_idx(), 0);
+    let d = arena.insert(3);
+    arena.remove(c);
+    assert_eq!(arena.repair_free_list(), 0);
+
+    let capacity = arena.capacity();
+    let slots: Vec<_> = (0..capacity - 2)
+        .map(|i| arena.try_insert(i as u32).unwrap().to_idx())
+        .collect();
+    assert_eq!(slots.last(), Some(&0));
+    assert!(!slots[..slots.len() - 1].contains(&0));
+    assert!(!slots.contains(&d.to_idx()));
+
+    // Shrinking cuts the tail off the free list, so removals must link after
+    // the new tail.
+    arena.clear();
+    arena.shrink_to(1);
+    let e = arena.insert(4);
+    arena.remove(e);
+    assert_eq!(arena.repair_free_list(), 0);
+}
+
+#[test]
+fn lifo_is_the_default() {
+    let mut arena: StandardArena<u32> = ArenaBuilder::new().capacity(4).build();
+    let indices: Vec<_> = (0..4).map(|i| arena.insert(i)).collect();
+    arena.remove(indices[0]);
+    arena.remove(indices[2]);
+    assert_eq!(arena.insert(9).to_idx(), 2);
+    // Without `no_grow` a full arena still grows.
+    arena.insert(10);
+    arena.insert(11);
+    assert_eq!(arena.len(), 5);
+}
+
+#[test]
+#[should_panic(expected = "arena is full and may not grow")]
+fn no_grow_insert_panics_when_full() {
+    let mut arena: StandardArena<u32> = ArenaBuilder::new().capacity(2).no_grow().build();
+    for i in 0..3 {
+        arena.insert(i);
+    }
+}
+
+#[test]
+fn additive_growth_adds_a_fixed_number_of_slots() {
+    let mut arena = StandardArena::with_capacity(20);
+    arena.set_growth(GrowthStrategy::Additive(8));
+    assert_eq!(arena.growth(), GrowthStrategy::Additive(8));
+
+    for i in 0..21 {
+        arena.insert(i);
+    }
+    assert_eq!(arena.capacity(), 28);
+    for i in 21..29 {
+        arena.insert(i);
+    }
+    assert_eq!(arena.capacity(), 36);
+
+    let mut built: StandardArena<u32> = ArenaBuilder::new()
+        .capacity(1)
+        .growth(GrowthStrategy::Exact)
+        .build();
+    built.insert(0);
+    built.insert(1);
+    assert_eq!(built.capacity(), 2);
+}
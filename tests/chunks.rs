@@ -0,0 +1,83 @@
+extern crate generational_arena_im;
+use generational_arena_im::StandardArena as Arena;
+
+#[test]
+fn chunks_of_three() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..10).map(|i| arena.insert(i)).collect();
+
+    let chunks: Vec<_> = arena.chunks(3).collect();
+    let sizes: Vec<_> = chunks.iter().map(|c| c.len()).collect();
+    assert_eq!(sizes, vec![3, 3, 3, 1]);
+
+    let flat: Vec<_> = chunks.into_iter().flatten().collect();
+    assert_eq!(flat.len(), 10);
+    for (i, (idx, value)) in flat.into_iter().enumerate() {
+        assert_eq!(idx, indices[i]);
+        assert_eq!(*value, i);
+    }
+}
+
+#[test]
+fn chunks_skip_holes() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..6).map(|i| arena.insert(i)).collect();
+    arena.remove(indices[1]);
+    arena.remove(indices[4]);
+
+    let chunks: Vec<Vec<_>> = arena
+        .chunks(3)
+        .map(|c| c.into_iter().map(|(_, v)| *v).collect())
+        .collect();
+    assert_eq!(chunks, vec![vec![0, 2, 3], vec![5]]);
+}
+
+#[test]
+#[should_panic]
+fn chunks_of_zero_panics() {
+    let arena: Arena<usize> = Arena::new();
+    let _ = arena.chunks(0);
+}
+
+#[test]
+fn split_live_at_partitions_elements() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..14).map(|i| arena.insert(i)).collect();
+    for idx in indices.iter().step_by(3).take(4) {
+        arena.remove(*idx);
+    }
+    assert_eq!(arena.len(), 10);
+
+    let (front, back) = arena.split_live_at(4);
+    let front: Vec<_> = front.map(|(_, v)| *v).collect();
+    let back: Vec<_> = back.map(|(_, v)| *v).collect();
+    assert_eq!(front, vec![1, 2, 4, 5]);
+    assert_eq!(back, vec![7, 8, 10, 11, 12, 13]);
+
+    let (front, back) = arena.split_live_at(20);
+    assert_eq!(front.count(), 10);
+    assert_eq!(back.count(), 0);
+}
+
+#[test]
+fn live_pairs_skip_holes() {
+    let mut arena = Arena::with_capacity(10);
+    let indices: Vec<_> = (0..10).map(|i| arena.insert(i)).collect();
+    for &i in &[0, 3, 4, 9] {
+        arena.remove(indices[i]);
+    }
+
+    let pairs: Vec<_> = arena.live_pairs().collect();
+    assert_eq!(pairs.len(), arena.len() - 1);
+    let values: Vec<_> = pairs.iter().map(|&((_, a), (_, b))| (*a, *b)).collect();
+    assert_eq!(values, vec![(1, 2), (2, 5), (5, 6), (6, 7), (7, 8)]);
+    for ((a, _), (b, _)) in pairs {
+        assert!(a.arr_idx() < b.arr_idx());
+        assert!(arena.contains(a) && arena.contains(b));
+    }
+
+    let mut single = Arena::new();
+    assert_eq!(single.live_pairs().count(), 0);
+    single.insert(1);
+    assert_eq!(single.live_pairs().count(), 0);
+}
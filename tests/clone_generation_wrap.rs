@@ -2,11 +2,13 @@ extern crate generational_arena_im;
 
 use generational_arena_im::{TinyWrapArena, TinyWrapIndex};
 
+type Snapshot = (TinyWrapArena<usize>, Vec<(TinyWrapIndex<usize>, usize)>);
+
 #[test]
 fn clone_insert_remove_until_wrap() {
-    const WRAP_LIMIT: usize = (std::u16::MAX as usize) + 10;
+    const WRAP_LIMIT: usize = (u16::MAX as usize) + 10;
     let mut arena = TinyWrapArena::<usize>::with_capacity(1);
-    let mut snaps: Vec<(TinyWrapArena<usize>, Vec<(TinyWrapIndex<usize>, usize)>)> = Vec::new();
+    let mut snaps: Vec<Snapshot> = Vec::new();
 
     // Insert enough elements to force capacity growth
     let a = arena.insert(0);
@@ -104,7 +104,7 @@ quickcheck! {
             for (idx, _val) in snap.later {
                 match snap.live.iter().find(|(i, _)| *i == idx) {
                     Some(&(_, orig)) => {
-                        if snap.arena.get(idx).map(|v| *v) != Some(orig) {
+                        if snap.arena.get(idx).copied() != Some(orig) {
                             return false;
                         }
                     }
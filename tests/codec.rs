@@ -0,0 +1,141 @@
+extern crate generational_arena_im;
+
+use generational_arena_im::{DecodeError, StandardArena, TinyWrapArena};
+
+fn round_trip(arena: &StandardArena<u32>) -> StandardArena<u32> {
+    let mut bytes = Vec::new();
+    arena.encode(&mut bytes);
+    StandardArena::decode(&bytes).unwrap()
+}
+
+#[test]
+fn encode_round_trip() {
+    let mut arena = StandardArena::new();
+    let indices: Vec<_> = (0..10).map(|i| arena.insert(i * 3)).collect();
+
+    let decoded = round_trip(&arena);
+    assert_eq!(decoded.len(), 10);
+    assert_eq!(decoded.capacity(), arena.capacity());
+    for (i, idx) in indices.iter().enumerate() {
+        assert_eq!(decoded[*idx], i as u32 * 3);
+    }
+}
+
+#[test]
+fn encode_round_trip_with_holes() {
+    let mut arena = StandardArena::new();
+    let indices: Vec<_> = (0..10).map(|i| arena.insert(i)).collect();
+    for idx in indices.iter().step_by(3) {
+        arena.remove(*idx);
+    }
+
+    let mut decoded = round_trip(&arena);
+    assert!(arena.iter().eq(decoded.iter()));
+    assert!(arena.debug_entries().eq(decoded.debug_entries()));
+    for idx in indices.iter().step_by(3) {
+        assert!(!decoded.contains(*idx));
+    }
+
+    // The free list and generation carry over, so both arenas reuse the
+    // same slots with the same generations.
+    for i in 0..6 {
+        assert_eq!(arena.insert(i), decoded.insert(i));
+    }
+}
+
+#[test]
+fn encode_round_trip_wrapping_generations() {
+    let mut arena = TinyWrapArena::new();
+    let idx = arena.insert(1u8);
+    arena.remove(idx);
+    let idx = arena.insert(2);
+
+    let mut bytes = Vec::new();
+    arena.encode(&mut bytes);
+    let decoded = TinyWrapArena::<u8>::decode(&bytes).unwrap();
+    assert_eq!(decoded[idx], 2);
+}
+
+#[test]
+fn decode_rejects_malformed_input() {
+    let mut arena = StandardArena::new();
+    arena.insert(1u32);
+    let mut bytes = Vec::new();
+    arena.encode(&mut bytes);
+
+    assert_eq!(
+        StandardArena::<u32>::decode(&bytes[..bytes.len() - 1]).unwrap_err(),
+        DecodeError::UnexpectedEnd
+    );
+
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert_eq!(
+        StandardArena::<u32>::decode(&trailing).unwrap_err(),
+        DecodeError::TrailingBytes
+    );
+
+    // The first slot's tag follows the 8-byte length, generation, capacity
+    // and free list head.
+    let mut bad_tag = bytes.clone();
+    bad_tag[32] = 7;
+    assert_eq!(
+        StandardArena::<u32>::decode(&bad_tag).unwrap_err(),
+        DecodeError::InvalidTag(7)
+    );
+
+    let mut bad_len = bytes;
+    bad_len[0] = 2;
+    assert_eq!(
+        StandardArena::<u32>::decode(&bad_len).unwrap_err(),
+        DecodeError::InvalidValue
+    );
+}
+
+#[test]
+fn decode_rejects_malformed_free_list() {
+    let mut arena = StandardArena::new();
+    let indices: Vec<_> = (0..3u32).map(|i| arena.insert(i)).collect();
+    arena.remove(indices[0]);
+    arena.remove(indices[1]);
+    let mut bytes = Vec::new();
+    arena.encode(&mut bytes);
+    assert!(StandardArena::<u32>::decode(&bytes).is_ok());
+
+    // Links are stored as the slot plus one. The free list head follows the
+    // 24-byte header, and slot 0's link follows slot 0's tag at byte 32.
+    let with_link = |at: usize, link: u64| {
+        let mut bytes = bytes.clone();
+        bytes[at..at + 8].copy_from_slice(&link.to_le_bytes());
+        StandardArena::<u32>::decode(&bytes).unwrap_err()
+    };
+    // The head points at the occupied slot 2.
+    assert_eq!(with_link(24, 3), DecodeError::InvalidValue);
+    // Slot 0 links back to slot 1, which links to slot 0.
+    assert_eq!(with_link(33, 2), DecodeError::InvalidValue);
+    // The list starts at slot 0 and never reaches slot 1.
+    assert_eq!(with_link(24, 1), DecodeError::InvalidValue);
+}
+
+#[test]
+fn to_records_pairs_payloads_with_slots() {
+    let mut arena = StandardArena::with_capacity(6);
+    let indices: Vec<_> = (0..6u32).map(|i| arena.insert(i * 0x0101_0101)).collect();
+    arena.remove(indices[1]);
+    arena.remove(indices[4]);
+    let reused = arena.insert(0xdead_beef);
+    assert_eq!(reused.to_idx(), 4);
+
+    let records: Vec<_> = arena.to_records(|v| v.to_le_bytes()).collect();
+    assert_eq!(records.len(), arena.len());
+    let slots: Vec<_> = records.iter().map(|&(_, slot, _)| slot).collect();
+    assert_eq!(slots, vec![0, 2, 3, 4, 5]);
+    for (payload, slot, generation) in records {
+        let value = u32::from_le_bytes(payload);
+        let idx = if slot == 4 { reused } else { indices[slot] };
+        assert_eq!(generation, idx.gen());
+        assert_eq!(arena[idx], value);
+    }
+    assert_eq!(reused.gen(), arena.generation());
+    assert!(indices[0].gen() != reused.gen());
+}
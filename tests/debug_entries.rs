@@ -0,0 +1,27 @@
+extern crate generational_arena_im;
+
+use generational_arena_im::{EntryKind, StandardArena};
+
+#[test]
+fn debug_entries_reports_layout_and_free_list() {
+    let mut arena = StandardArena::with_capacity(4);
+    let indices: Vec<_> = (0..3).map(|i| arena.insert(i)).collect();
+    arena.remove(indices[0]);
+    arena.remove(indices[2]);
+
+    let entries: Vec<_> = arena.debug_entries().collect();
+    assert_eq!(entries.len(), 4);
+    assert_eq!(
+        entries[1],
+        (
+            1,
+            EntryKind::Occupied {
+                generation: indices[1].gen()
+            }
+        )
+    );
+    // The most recently freed slot heads the free list: 2 -> 0 -> 3.
+    assert_eq!(entries[2], (2, EntryKind::Free { next_free: Some(0) }));
+    assert_eq!(entries[0], (0, EntryKind::Free { next_free: Some(3) }));
+    assert_eq!(entries[3], (3, EntryKind::Free { next_free: None }));
+}
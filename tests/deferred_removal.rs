@@ -0,0 +1,131 @@
+extern crate generational_arena_im;
+use generational_arena_im::{
+    Arena as GenericArena, ArenaBuilder, EntryKind, FixedGenerationalIndex, FreePolicy,
+    GenerationalIndex, RemovalUnsupported, StandardArena as Arena,
+};
+
+#[test]
+fn deferred_remove_is_stale_immediately_and_reused_after_commit() {
+    let mut arena = Arena::with_capacity(4);
+    arena.set_deferred_removal(true);
+    assert!(arena.deferred_removal());
+    let indices: Vec<_> = (0..4).map(|i| arena.insert(i)).collect();
+    let generation = arena.generation();
+
+    assert_eq!(arena.remove(indices[1]), Some(1));
+    assert_eq!(arena.remove(indices[1]), None);
+    assert!(!arena.contains(indices[1]));
+    assert_eq!(arena.len(), 3);
+    assert_eq!(arena.generation(), generation);
+    assert!(!arena.has_free());
+
+    // Until the removal is committed, its slot is not reused.
+    let grown = arena.insert(10);
+    assert_eq!(grown.to_idx(), 4);
+    assert_eq!(arena.capacity(), 8);
+
+    arena.retain(|_, v| *v != 3);
+    assert!(!arena.contains(indices[3]));
+    let free_before = arena.free_count();
+
+    assert_eq!(arena.commit_removals(), 2);
+    assert_eq!(arena.commit_removals(), 0);
+    assert_eq!(arena.free_count(), free_before);
+    assert!(arena.generation() != generation);
+
+    // The committed slots are reused at a newer generation, so the removed
+    // indices stay stale.
+    let mut reused = Vec::new();
+    while reused.len() < 5 {
+        reused.push(arena.insert(20));
+    }
+    assert!(reused.iter().any(|idx| idx.to_idx() == 1));
+    assert!(reused.iter().any(|idx| idx.to_idx() == 3));
+    assert!(!arena.contains(indices[1]));
+    assert!(!arena.contains(indices[3]));
+    assert_eq!(arena.capacity(), 8);
+}
+
+#[test]
+fn turning_off_deferral_commits() {
+    let mut arena: Arena<u32> = ArenaBuilder::new()
+        .capacity(2)
+        .free_policy(FreePolicy::Fifo)
+        .build();
+    arena.set_deferred_removal(true);
+    let a = arena.insert(1);
+    let b = arena.insert(2);
+    arena.remove(a);
+    arena.remove(b);
+    assert_eq!(arena.free_count(), 2);
+    assert!(!arena.has_free());
+
+    arena.set_deferred_removal(false);
+    assert_eq!(arena.repair_free_list(), 0);
+    let c = arena.insert(3);
+    let d = arena.insert(4);
+    assert_eq!((c.to_idx(), d.to_idx()), (0, 1));
+    assert_eq!(arena.capacity(), 2);
+
+    // Without deferral, removals free their slots straight away.
+    arena.remove(c);
+    assert!(arena.has_free());
+}
+
+#[test]
+fn shrinking_commits_pending_removals() {
+    let mut arena = Arena::with_capacity(4);
+    arena.set_deferred_removal(true);
+    let indices: Vec<_> = (0..4).map(|i| arena.insert(i)).collect();
+    arena.remove(indices[3]);
+    arena.remove(indices[2]);
+
+    arena.shrink_to(0);
+    assert_eq!(arena.capacity(), 2);
+    assert_eq!(arena.commit_removals(), 0);
+    assert_eq!(arena.repair_free_list(), 0);
+
+    let idx = arena.insert(7);
+    assert_eq!(idx.to_idx(), 2);
+    assert!(!arena.contains(indices[2]));
+}
+
+/// A generation whose removals cannot advance it, as a careless
+/// implementation might.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct StuckGeneration;
+
+impl FixedGenerationalIndex for StuckGeneration {
+    fn first_generation() -> Self {
+        StuckGeneration
+    }
+    fn generation_lt(&self, _other: &Self) -> bool {
+        false
+    }
+    fn increment_for_removal(&mut self) -> Result<(), RemovalUnsupported> {
+        Err(RemovalUnsupported)
+    }
+}
+
+impl GenerationalIndex for StuckGeneration {
+    fn increment_generation(&mut self) {}
+}
+
+#[test]
+fn commit_retires_slots_when_the_generation_cannot_advance() {
+    let mut arena: GenericArena<u32, usize, StuckGeneration> = GenericArena::with_capacity(2);
+    arena.set_deferred_removal(true);
+    let a = arena.insert(1);
+    arena.insert(2);
+    assert_eq!(arena.remove(a), Some(1));
+
+    assert_eq!(arena.commit_removals(), 0);
+    assert_eq!(
+        arena.debug_entries().next(),
+        Some((a.to_idx(), EntryKind::Retired))
+    );
+    // The stale index never matches a new element.
+    let b = arena.insert(3);
+    assert_ne!(b.to_idx(), a.to_idx());
+    assert!(!arena.contains(a));
+}
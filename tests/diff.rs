@@ -0,0 +1,99 @@
+extern crate generational_arena_im;
+use generational_arena_im::{ArenaOp, SlotDelta, StandardArena as Arena};
+
+#[test]
+fn changes_since_matches_diff() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..200).map(|i| arena.insert(i)).collect();
+    let snapshot = arena.clone();
+
+    arena.remove(indices[3]);
+    arena[indices[10]] = 1000;
+    arena[indices[11]] = 11; // unchanged value
+    let reused = arena.insert(2000);
+    assert_eq!(reused.arr_idx(), 3);
+    arena.remove(indices[150]);
+    let mut grown = Vec::new();
+    while arena.len() < 250 {
+        grown.push(arena.insert(3000));
+    }
+
+    let lazy: Vec<_> = arena.changes_since(&snapshot).collect();
+    assert_eq!(lazy, arena.diff(&snapshot));
+
+    let mut expected = vec![
+        ArenaOp::Remove(indices[3]),
+        ArenaOp::Insert(reused, &2000),
+        ArenaOp::Change(indices[10], &1000),
+    ];
+    let first_grown = grown[0];
+    expected.push(ArenaOp::Remove(indices[150]));
+    assert_eq!(&lazy[..4], &expected[..]);
+    // `first_grown` reuses slot 150 and the rest land in new capacity.
+    assert_eq!(lazy[4], ArenaOp::Insert(first_grown, &3000));
+    assert_eq!(lazy.len(), 4 + grown.len());
+}
+
+#[test]
+fn identical_snapshot_has_no_changes() {
+    let arena: Arena<usize> = (0..1000).collect();
+    let snapshot = arena.clone();
+    assert_eq!(arena.changes_since(&snapshot).count(), 0);
+    assert!(arena.diff(&arena).is_empty());
+}
+
+#[test]
+fn removed_then_empty() {
+    let mut arena = Arena::new();
+    let a = arena.insert(1);
+    let snapshot = arena.clone();
+    arena.remove(a);
+    assert_eq!(arena.diff(&snapshot), vec![ArenaOp::Remove(a)]);
+    assert_eq!(snapshot.diff(&arena), vec![ArenaOp::Insert(a, &1)]);
+}
+
+#[test]
+fn slot_states_zip_reports_each_slot() {
+    let mut arena = Arena::with_capacity(200);
+    let indices: Vec<_> = (0..200).map(|i| arena.insert(i)).collect();
+    arena.remove(indices[7]);
+    let snapshot = arena.clone();
+
+    arena.remove(indices[3]);
+    arena[indices[10]] = 1000;
+    arena[indices[11]] = 11; // unchanged value
+    let refilled = arena.insert(2000);
+    assert_eq!(refilled.arr_idx(), 3);
+    let filled = arena.insert(3000);
+    assert_eq!(filled.arr_idx(), 7);
+    arena.remove(indices[150]);
+    arena.insert(4000);
+    arena.insert(5000);
+
+    let deltas: Vec<_> = snapshot.slot_states_zip(&arena).collect();
+    assert_eq!(deltas.len(), arena.capacity());
+    let changed: Vec<_> = deltas
+        .iter()
+        .filter(|&&(_, delta)| delta != SlotDelta::Same)
+        .cloned()
+        .collect();
+    assert_eq!(
+        changed,
+        vec![
+            (3, SlotDelta::ValueChanged(&2000)),
+            (7, SlotDelta::BecameOccupied(&3000)),
+            (10, SlotDelta::ValueChanged(&1000)),
+            (150, SlotDelta::ValueChanged(&4000)),
+            (200, SlotDelta::BecameOccupied(&5000)),
+        ]
+    );
+
+    // Going backwards, the slots grown into count as becoming free.
+    let reverse: Vec<_> = arena.slot_states_zip(&snapshot).collect();
+    assert_eq!(reverse[7], (7, SlotDelta::BecameFree));
+    assert_eq!(reverse[200], (200, SlotDelta::BecameFree));
+    assert_eq!(reverse[201], (201, SlotDelta::Same));
+    assert!(snapshot
+        .slot_states_zip(&snapshot)
+        .all(|(_, delta)| delta == SlotDelta::Same));
+}
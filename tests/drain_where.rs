@@ -0,0 +1,32 @@
+extern crate generational_arena_im;
+use generational_arena_im::StandardArena as Arena;
+
+#[test]
+fn drain_where_removes_remaining_matches_on_drop() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..10).map(|i| arena.insert(i)).collect();
+
+    let first_two: Vec<_> = arena.drain_where(|v| v % 3 == 0).take(2).collect();
+    assert_eq!(first_two, vec![(indices[0], 0), (indices[3], 3)]);
+
+    // 6 and 9 were never yielded, but are removed all the same.
+    assert_eq!(arena.len(), 6);
+    for (i, idx) in indices.iter().enumerate() {
+        assert_eq!(arena.contains(*idx), i % 3 != 0);
+    }
+}
+
+#[test]
+fn drain_where_yields_all_matches_in_slot_order() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..10).map(|i| arena.insert(i)).collect();
+    arena.remove(indices[2]);
+
+    let drained: Vec<_> = arena.drain_where(|v| *v < 5).map(|(_, v)| v).collect();
+    assert_eq!(drained, vec![0, 1, 3, 4]);
+    let kept: Vec<_> = arena.iter().map(|(_, v)| *v).collect();
+    assert_eq!(kept, vec![5, 6, 7, 8, 9]);
+
+    assert_eq!(arena.drain_where(|v| *v > 100).count(), 0);
+    assert_eq!(arena.len(), 5);
+}
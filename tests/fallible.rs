@@ -0,0 +1,133 @@
+extern crate generational_arena_im;
+use generational_arena_im::{CapacityOverflow, NanoArena, SmallPtrSlab, StandardArena, TinyArena};
+
+#[test]
+fn checked_insert_reports_exhausted_index_space() {
+    let mut arena = NanoArena::new();
+    for i in 0..256 {
+        assert!(arena.checked_insert(i).is_ok());
+    }
+    assert_eq!(arena.capacity(), 256);
+    assert_eq!(arena.checked_insert(256), Err(256));
+    assert_eq!(arena.len(), 256);
+}
+
+#[test]
+fn try_reserve_rejects_unaddressable_slots() {
+    let mut arena = NanoArena::<u8>::with_capacity(200);
+    assert_eq!(arena.try_reserve(57), Err(CapacityOverflow));
+    assert_eq!(arena.capacity(), 200);
+    assert_eq!(arena.try_reserve(56), Ok(()));
+    assert_eq!(arena.capacity(), 256);
+    assert_eq!(arena.try_reserve(0), Ok(()));
+
+    let mut arena = StandardArena::<u8>::new();
+    assert_eq!(arena.try_reserve(usize::MAX), Err(CapacityOverflow));
+}
+
+#[test]
+fn try_reserve_respects_nonzero_indices() {
+    let mut slab = SmallPtrSlab::<u8>::with_capacity(1);
+    assert!(slab.try_reserve(u32::MAX as usize).is_err());
+    assert_eq!(slab.capacity(), 1);
+}
+
+#[test]
+fn fallible_lookups_never_panic() {
+    let mut arena = StandardArena::new();
+    let idx = arena.insert(1);
+    let other: StandardArena<i32> = StandardArena::with_capacity(1);
+    arena.remove(idx);
+    assert!(arena.get(idx).is_none());
+    assert!(arena.get_mut(idx).is_none());
+    assert!(arena.remove(idx).is_none());
+
+    let mut big = StandardArena::new();
+    let far = (0..100).map(|i| big.insert(i)).last().unwrap();
+    assert!(other.get(far).is_none());
+}
+
+#[test]
+fn try_resolve_reports_aliasing() {
+    let mut arena = StandardArena::new();
+    let a = arena.insert(1);
+    assert!(arena.borrows().mutable(a).shared(a).try_resolve().is_none());
+}
+
+#[test]
+fn reserve_to_index_max_fills_the_index_space() {
+    let mut arena = TinyArena::<u8>::new();
+    let idx = arena.insert(1);
+    arena.reserve_to_index_max().unwrap();
+    assert_eq!(arena.capacity(), u16::MAX as usize + 1);
+    assert_eq!(arena[idx], 1);
+    assert!(arena.try_reserve(1).is_err());
+
+    // Every slot is usable without growing.
+    let mut count = 1;
+    while arena.try_insert(0).is_ok() {
+        count += 1;
+    }
+    assert_eq!(count, arena.capacity());
+
+    // Calling it again is a no-op.
+    arena.reserve_to_index_max().unwrap();
+    assert_eq!(arena.capacity(), u16::MAX as usize + 1);
+}
+
+#[test]
+fn reserve_to_index_max_refuses_usize_indices() {
+    let mut arena = StandardArena::<u8>::new();
+    let capacity = arena.capacity();
+    assert!(arena.reserve_to_index_max().is_err());
+    assert_eq!(arena.capacity(), capacity);
+}
+
+#[test]
+fn insert_below_respects_the_slot_bound() {
+    let mut arena = StandardArena::with_capacity(1);
+    let indices: Vec<_> = (0..4).map(|i| arena.insert_below(4, i).unwrap()).collect();
+    assert!(indices.iter().all(|idx| idx.to_idx() < 4));
+    assert_eq!(arena.insert_below(4, 4), Err(4));
+    assert_eq!(arena.capacity(), 4);
+
+    // A freed slot below the bound is reused, but one above it is not.
+    arena.remove(indices[1]);
+    assert_eq!(arena.insert_below(4, 5).map(|idx| idx.to_idx()), Ok(1));
+    let above = arena.insert(6);
+    arena.remove(above);
+    assert_eq!(arena.insert_below(4, 7), Err(7));
+}
+
+#[test]
+fn reserve_and_seal_stops_growth_until_cleared() {
+    let mut arena = StandardArena::with_capacity(1);
+    arena.reserve_and_seal(4);
+    assert_eq!(arena.capacity(), 4);
+
+    let indices: Vec<_> = (0..4).map(|i| arena.checked_insert(i).unwrap()).collect();
+    assert_eq!(arena.checked_insert(4), Err(4));
+    assert_eq!(arena.capacity(), 4);
+
+    // Free slots can still be filled.
+    arena.remove(indices[0]);
+    assert!(arena.try_insert(5).is_ok());
+    assert_eq!(arena.checked_insert(6), Err(6));
+
+    arena.clear();
+    for i in 0..5 {
+        arena.insert(i);
+    }
+    assert!(arena.capacity() > 4);
+}
+
+#[test]
+#[should_panic(expected = "arena is full and may not grow")]
+fn insert_into_a_sealed_arena_panics() {
+    let mut arena = StandardArena::new();
+    arena.reserve_and_seal(1);
+    while arena.has_free() {
+        arena.insert(0);
+    }
+    arena.insert(1);
+}
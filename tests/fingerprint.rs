@@ -0,0 +1,48 @@
+extern crate generational_arena_im;
+use generational_arena_im::StandardArena as Arena;
+
+fn build() -> Arena<u32> {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..20).map(|i| arena.insert(i * 7)).collect();
+    arena.remove(indices[3]);
+    arena.remove(indices[11]);
+    arena
+}
+
+#[test]
+fn content_fingerprint_is_deterministic() {
+    let a = build();
+    let b = build();
+    assert_eq!(a.content_fingerprint(), b.content_fingerprint());
+    assert_eq!(a.content_fingerprint(), a.content_fingerprint());
+    assert_eq!(a.content_fingerprint(), a.clone().content_fingerprint());
+
+    // The fingerprint does not depend on the process, so it can be pinned.
+    assert_eq!(a.content_fingerprint(), 5_359_192_484_866_162_313);
+}
+
+#[test]
+fn content_fingerprint_covers_slots_generations_and_values() {
+    let base = build().content_fingerprint();
+
+    let mut changed_value = build();
+    let (idx, _) = changed_value.iter().next().unwrap();
+    changed_value[idx] += 1;
+    assert_ne!(changed_value.content_fingerprint(), base);
+
+    // Reinserting the same value gives it a newer generation.
+    let mut changed_generation = build();
+    let (idx, value) = changed_generation
+        .iter()
+        .map(|(i, v)| (i, *v))
+        .last()
+        .unwrap();
+    changed_generation.remove(idx);
+    changed_generation.insert(value);
+    assert_ne!(changed_generation.content_fingerprint(), base);
+
+    // Spare capacity is not part of the contents.
+    let mut reserved = build();
+    reserved.reserve(10);
+    assert_eq!(reserved.content_fingerprint(), base);
+}
@@ -0,0 +1,15 @@
+extern crate generational_arena_im;
+
+use generational_arena_im::StandardArena;
+
+#[test]
+fn half_removed_is_half_fragmented() {
+    let mut arena = StandardArena::with_capacity(10);
+    let indices: Vec<_> = (0..10).map(|i| arena.insert(i)).collect();
+    assert_eq!(arena.fragmentation(), 0.0);
+
+    for idx in indices.iter().step_by(2) {
+        arena.remove(*idx);
+    }
+    assert!((arena.fragmentation() - 0.5).abs() < 1e-9);
+}
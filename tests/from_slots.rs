@@ -0,0 +1,34 @@
+extern crate generational_arena_im;
+use generational_arena_im::{StandardArena as Arena, StandardIndex as Index};
+
+#[test]
+fn from_slots_preserves_positions() {
+    let mut arena = Arena::from_slots(vec![Some(1), None, Some(3)]);
+    assert_eq!(arena.len(), 2);
+    assert_eq!(arena.capacity(), 3);
+    assert_eq!(arena.get(Index::from_idx_first_gen(0)), Some(&1));
+    assert_eq!(arena.get(Index::from_idx_first_gen(1)), None);
+    assert_eq!(arena.get(Index::from_idx_first_gen(2)), Some(&3));
+
+    // The only free slot is reused first, then the arena grows.
+    let idx = arena.try_insert(2).unwrap();
+    assert_eq!(idx.to_idx(), 1);
+    assert!(arena.try_insert(4).is_err());
+}
+
+#[test]
+fn from_slots_free_list_is_ascending() {
+    let mut arena: Arena<usize> = Arena::from_slots(vec![None, Some(1), None, None, Some(4)]);
+    let slots: Vec<_> = (0..3)
+        .map(|i| arena.try_insert(i).unwrap().to_idx())
+        .collect();
+    assert_eq!(slots, vec![0, 2, 3]);
+}
+
+#[test]
+fn from_slots_empty() {
+    let mut arena: Arena<usize> = Arena::from_slots(Vec::new());
+    assert!(arena.is_empty());
+    let idx = arena.insert(5);
+    assert_eq!(arena[idx], 5);
+}
@@ -0,0 +1,35 @@
+extern crate generational_arena_im;
+extern crate rayon;
+
+use generational_arena_im::{FrozenArena, NonzeroGeneration, StandardArena};
+use rayon::iter::ParallelIterator;
+
+fn total(frozen: &FrozenArena<u32, usize, NonzeroGeneration<usize>>) -> u32 {
+    frozen.iter().map(|(_, v)| *v).sum()
+}
+
+#[test]
+fn frozen_arena_reads_then_thaws() {
+    let mut arena = StandardArena::new();
+    let a = arena.insert(1);
+    let b = arena.insert(2);
+    let c = arena.insert(3);
+    arena.remove(b);
+
+    let frozen = arena.freeze();
+    assert_eq!(frozen.len(), 2);
+    assert!(!frozen.is_empty());
+    assert!(frozen.contains(a));
+    assert!(!frozen.contains(b));
+    assert_eq!(frozen.get(c), Some(&3));
+    assert_eq!(frozen[a], 1);
+    assert_eq!(total(&frozen), 4);
+    assert_eq!(frozen.par_iter().map(|(_, v)| *v).sum::<u32>(), 4);
+    assert_eq!((&frozen).into_iter().count(), 2);
+
+    let mut arena = frozen.thaw();
+    arena[a] = 10;
+    arena.insert(5);
+    assert_eq!(arena.len(), 3);
+    assert_eq!(arena[a], 10);
+}
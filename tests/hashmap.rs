@@ -0,0 +1,41 @@
+extern crate generational_arena_im;
+use generational_arena_im::StandardArena as Arena;
+
+#[test]
+fn hashmap_keys_are_slots() {
+    let mut arena = Arena::new();
+    let a = arena.insert("a");
+    let b = arena.insert("b");
+    let c = arena.insert("c");
+    arena.remove(b);
+
+    let map = arena.to_hashmap();
+    assert_eq!(map.len(), 2);
+    assert_eq!(map[&a.to_idx()], "a");
+    assert_eq!(map[&c.to_idx()], "c");
+    assert!(!map.contains_key(&b.to_idx()));
+
+    assert_eq!(arena.into_hashmap(), map);
+}
+
+#[test]
+fn remove_set_removes_exactly_the_set() {
+    use std::collections::HashSet;
+
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..10).map(|i| arena.insert(i)).collect();
+    let stale = indices[9];
+    arena.remove(stale);
+    let stale_and_reused = arena.insert(9);
+    assert_eq!(stale_and_reused.to_idx(), stale.to_idx());
+
+    let set: HashSet<_> = vec![indices[1], indices[4], indices[7], stale]
+        .into_iter()
+        .collect();
+    assert_eq!(arena.remove_set(&set), 3);
+    assert_eq!(arena.len(), 7);
+    for (i, idx) in indices.iter().enumerate().take(9) {
+        assert_eq!(arena.contains(*idx), ![1, 4, 7].contains(&i));
+    }
+    assert!(arena.contains(stale_and_reused));
+}
@@ -87,3 +87,22 @@ fn small_ptr_slab_index_roundtrip_edges() {
     check_roundtrip::<(), NonZeroIndex<u32>, DisableRemoval>(max, DisableRemoval);
 }
 
+
+#[test]
+fn untyped_index_roundtrip() {
+    let mut numbers = StandardArena::new();
+    let mut names = StandardArena::new();
+    let number = numbers.insert(7);
+    let name = names.insert("seven");
+
+    let handles: Vec<RawIndex<usize, NonzeroGeneration<usize>>> =
+        vec![number.into_raw_untyped(), name.into_raw_untyped()];
+    assert_eq!(handles[0].arr_idx(), number.arr_idx());
+    assert_eq!(handles[0].gen(), number.gen());
+
+    let number_again: StandardIndex<i32> = Index::from_raw_untyped(handles[0]);
+    let name_again: StandardIndex<&str> = Index::from_raw_untyped(handles[1]);
+    assert_eq!(number_again, number);
+    assert_eq!(numbers[number_again], 7);
+    assert_eq!(names[name_again], "seven");
+}
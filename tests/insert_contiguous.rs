@@ -0,0 +1,49 @@
+extern crate generational_arena_im;
+
+use generational_arena_im::{NanoArena, StandardArena};
+
+#[test]
+fn batch_after_removals_is_consecutive() {
+    let mut arena = StandardArena::new();
+    let indices: Vec<_> = (0..10).map(|i| arena.insert(i)).collect();
+    for idx in indices.iter().step_by(2) {
+        arena.remove(*idx);
+    }
+    let capacity = arena.capacity();
+
+    let batch = arena.insert_contiguous(100..105);
+    assert_eq!(batch.len(), 5);
+    for (offset, idx) in batch.iter().enumerate() {
+        assert_eq!(idx.to_idx(), capacity + offset);
+        assert_eq!(arena[*idx], 100 + offset);
+    }
+    assert_eq!(arena.len(), 10);
+
+    // The holes are still available to ordinary inserts.
+    let refill = arena.insert(7);
+    assert!(refill.to_idx() < capacity);
+}
+
+#[test]
+fn empty_batch_is_a_no_op() {
+    let mut arena = StandardArena::<u8>::new();
+    let capacity = arena.capacity();
+    assert!(arena.insert_contiguous(Vec::new().into_iter()).is_empty());
+    assert_eq!(arena.len(), 0);
+    assert_eq!(arena.capacity(), capacity);
+}
+
+#[test]
+#[should_panic(expected = "arena is at its maximum length")]
+fn batch_respects_max_len() {
+    let mut arena = StandardArena::new();
+    arena.set_max_len(3);
+    arena.insert_contiguous(0..4);
+}
+
+#[test]
+#[should_panic(expected = "arena index space exhausted")]
+fn batch_beyond_index_space_panics() {
+    let mut arena = NanoArena::new();
+    arena.insert_contiguous(0..257);
+}
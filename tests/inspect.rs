@@ -0,0 +1,65 @@
+extern crate generational_arena_im;
+
+use generational_arena_im::{SlotState, StandardArena, StandardIndex, TinyWrapArena};
+
+#[test]
+fn inspect_reports_each_slot_state() {
+    let mut arena = StandardArena::with_capacity(2);
+    let a = arena.insert('a');
+    assert_eq!(arena.inspect(a), SlotState::Live(&'a'));
+
+    arena.remove(a);
+    assert_eq!(arena.inspect(a), SlotState::Free);
+
+    let b = arena.insert('b');
+    assert_eq!(b.to_idx(), a.to_idx());
+    assert_eq!(
+        arena.inspect(a),
+        SlotState::Recycled {
+            current_gen: b.gen(),
+            value: &'b'
+        }
+    );
+    assert_eq!(arena.inspect(b), SlotState::Live(&'b'));
+
+    let far = StandardIndex::from_raw(100, b.gen());
+    assert_eq!(arena.inspect(far), SlotState::OutOfRange);
+}
+
+#[test]
+fn staleness_counts_generations_since_recycle() {
+    let mut arena = StandardArena::with_capacity(2);
+    let a = arena.insert('a');
+    assert_eq!(arena.staleness(a), None);
+
+    arena.remove(a);
+    assert_eq!(arena.staleness(a), None);
+
+    let b = arena.insert('b');
+    assert_eq!(arena.staleness(a), Some(1));
+    assert_eq!(arena.staleness(b), None);
+
+    // Removals elsewhere in the arena also advance the generation.
+    let c = arena.insert('c');
+    arena.remove(c);
+    arena.remove(b);
+    arena.insert('d');
+    assert_eq!(arena.staleness(b), Some(2));
+    assert_eq!(arena.staleness(a), Some(3));
+}
+
+#[test]
+fn staleness_on_wrapping_generations() {
+    let mut arena = TinyWrapArena::with_capacity(1);
+    let first = arena.insert(0u8);
+    arena.remove(first);
+    let mut last = arena.insert(1);
+    assert_eq!(arena.staleness(first), Some(1));
+
+    // Far enough around the counter, the generations are no longer ordered.
+    for _ in 0..40_000 {
+        arena.remove(last);
+        last = arena.insert(1);
+    }
+    assert_eq!(arena.staleness(first), None);
+}
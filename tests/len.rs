@@ -2,7 +2,7 @@ extern crate generational_arena_im;
 extern crate rayon;
 
 use generational_arena_im::StandardArena as Arena;
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use rayon::iter::{IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator};
 
 #[test]
 fn par_iter_len_invariant() {
@@ -67,3 +67,63 @@ fn par_iter_middle_removed_len_is_two() {
     let v: Vec<_> = arena.par_iter().collect();
     assert_eq!(v.len(), 2, "expected 2 occupied, got {}", v.len());
 }
+
+/// Without holes the length is known up front, so `collect` can allocate
+/// exactly once.
+#[test]
+fn par_iter_opt_len_presizes_collect() {
+    let mut arena = Arena::with_capacity(1000);
+    for i in 0..1000 {
+        arena.insert(i);
+    }
+    assert_eq!(arena.par_iter().opt_len(), Some(1000));
+    assert_eq!(arena.par_iter_mut().opt_len(), Some(1000));
+
+    let v: Vec<_> = arena.par_iter().map(|(_, x)| *x).collect();
+    assert_eq!(v.len(), 1000);
+    assert_eq!(v.capacity(), 1000);
+}
+
+/// Holes do not count towards the reported length.
+#[test]
+fn par_iter_opt_len_with_holes() {
+    let mut arena = Arena::new();
+    let k0 = arena.insert(0);
+    arena.insert(1);
+    arena.remove(k0);
+    assert_eq!(arena.par_iter().opt_len(), Some(1));
+    assert_eq!(arena.par_iter_mut().opt_len(), Some(1));
+
+    let v: Vec<_> = arena.par_iter().map(|(_, x)| *x).collect();
+    assert_eq!(v, vec![1]);
+}
+
+/// Splitting at every few elements agrees with sequential iteration, however
+/// the holes fall relative to the split points.
+#[test]
+fn par_iter_fine_splits_match_sequential() {
+    use rayon::iter::IndexedParallelIterator;
+
+    let mut arena = Arena::new();
+    let keys: Vec<_> = (0..1000).map(|i| arena.insert(i)).collect();
+    for (i, k) in keys.iter().enumerate() {
+        if i % 7 == 0 || (200..400).contains(&i) || i % 64 == 63 {
+            arena.remove(*k);
+        }
+    }
+    let seq: Vec<_> = arena.iter().map(|(k, x)| (k, *x)).collect();
+
+    let par: Vec<_> = arena
+        .par_iter()
+        .with_max_len(3)
+        .map(|(k, x)| (k, *x))
+        .collect();
+    assert_eq!(par, seq);
+
+    let par_mut: Vec<_> = arena
+        .par_iter_mut()
+        .with_max_len(5)
+        .map(|(k, x)| (k, *x))
+        .collect();
+    assert_eq!(par_mut, seq);
+}
@@ -0,0 +1,83 @@
+extern crate generational_arena_im;
+use generational_arena_im::StandardArena as Arena;
+
+#[test]
+fn max_len_limits_inserts() {
+    let mut arena = Arena::with_capacity(1);
+    arena.set_max_len(3);
+    assert_eq!(arena.max_len(), Some(3));
+
+    let a = arena.insert_within_max_len("a").unwrap();
+    arena.insert_within_max_len("b").unwrap();
+    arena.insert_within_max_len("c").unwrap();
+    assert_eq!(arena.insert_within_max_len("d"), Err("d"));
+    assert_eq!(arena.checked_insert("d"), Err("d"));
+    assert_eq!(arena.try_insert("d"), Err("d"));
+    assert_eq!(arena.len(), 3);
+
+    // Removing an element makes room again.
+    arena.remove(a);
+    assert!(arena.insert_within_max_len("d").is_ok());
+    assert_eq!(arena.insert_within_max_len("e"), Err("e"));
+
+    // Clearing the limit lets the arena grow without bound.
+    arena.clear_max_len();
+    assert_eq!(arena.max_len(), None);
+    for _ in 0..10 {
+        arena.insert_within_max_len("f").unwrap();
+    }
+    assert_eq!(arena.len(), 13);
+}
+
+#[test]
+fn max_len_is_independent_of_capacity() {
+    let mut arena = Arena::with_capacity(100);
+    arena.set_max_len(2);
+    arena.insert(0);
+    arena.insert(1);
+    assert_eq!(arena.try_insert(2), Err(2));
+    assert_eq!(arena.capacity(), 100);
+}
+
+#[test]
+#[should_panic(expected = "maximum length")]
+fn insert_beyond_max_len_panics() {
+    let mut arena = Arena::new();
+    arena.set_max_len(0);
+    arena.insert(0);
+}
+
+#[test]
+fn insert_or_evict_replaces_lowest_slot() {
+    let mut arena = Arena::new();
+    arena.set_max_len(3);
+    let first: Vec<_> = (0..3).map(|i| arena.insert_or_evict(i)).collect();
+    assert!(first.iter().all(|(_, evicted)| evicted.is_none()));
+    let first: Vec<_> = first.into_iter().map(|(idx, _)| idx).collect();
+    assert_eq!(first[0].to_idx(), 0);
+
+    let (d, evicted) = arena.insert_or_evict(3);
+    assert_eq!(evicted, Some((first[0], 0)));
+    assert!(!arena.contains(first[0]));
+    assert_eq!(d.to_idx(), 0);
+    assert_eq!(arena.len(), 3);
+    assert_eq!(arena[d], 3);
+
+    // The new element sits in the lowest slot, so it is evicted next.
+    let (e, evicted) = arena.insert_or_evict(4);
+    assert_eq!(evicted, Some((d, 3)));
+    assert_eq!(e.to_idx(), 0);
+
+    // Below the limit nothing is evicted, even with an older element in a
+    // higher slot.
+    arena.remove(e);
+    let (f, evicted) = arena.insert_or_evict(5);
+    assert_eq!(evicted, None);
+    assert_eq!(f.to_idx(), 0);
+    assert!(arena.contains(first[1]));
+    assert!(arena.contains(first[2]));
+
+    let mut values: Vec<_> = arena.iter().map(|(_, v)| *v).collect();
+    values.sort();
+    assert_eq!(values, vec![1, 2, 5]);
+}
@@ -0,0 +1,55 @@
+extern crate generational_arena_im;
+
+use generational_arena_im::{NanoArena, TinyWrapArena};
+
+#[test]
+fn newer_than_orders_nearby_generations() {
+    let mut arena = TinyWrapArena::new();
+    let first = arena.insert(0);
+    arena.remove(first);
+    let second = arena.insert(1);
+
+    assert_eq!(second.newer_than(&first), Some(true));
+    assert_eq!(first.newer_than(&second), Some(false));
+    assert_eq!(first.newer_than(&first), Some(false));
+}
+
+#[test]
+fn newer_than_is_none_across_a_wrap() {
+    let mut arena = TinyWrapArena::with_capacity(1);
+    let first = arena.insert(0);
+    let mut idx = first;
+    let mut before_wrap = first;
+    // Cycle the single slot until the generation counter wraps back around.
+    loop {
+        arena.remove(idx);
+        idx = arena.insert(0);
+        if idx.gen().to_usize() < before_wrap.gen().to_usize() {
+            break;
+        }
+        before_wrap = idx;
+    }
+    assert_eq!(idx.to_idx(), before_wrap.to_idx());
+    assert_eq!(idx.newer_than(&before_wrap), None);
+    assert_eq!(before_wrap.newer_than(&idx), None);
+}
+
+#[test]
+fn newer_than_is_none_across_a_wrapping_integer_wrap() {
+    let mut arena = NanoArena::with_capacity(1);
+    let mut idx = arena.insert(0);
+    let mut before_wrap = idx;
+    loop {
+        arena.remove(idx);
+        idx = arena.insert(0);
+        if idx.gen() < before_wrap.gen() {
+            break;
+        }
+        assert_eq!(idx.newer_than(&before_wrap), Some(true));
+        before_wrap = idx;
+    }
+    assert_eq!(before_wrap.gen().0, u8::MAX);
+    assert_eq!(idx.gen().0, 0);
+    assert_eq!(idx.newer_than(&before_wrap), None);
+    assert_eq!(before_wrap.newer_than(&idx), None);
+}
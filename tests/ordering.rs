@@ -0,0 +1,49 @@
+extern crate generational_arena_im;
+use generational_arena_im::StandardArena as Arena;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeSet;
+use std::hash::{Hash, Hasher};
+
+fn hash_of<T: Hash>(t: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    t.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn arenas_order_lexicographically_by_slot_and_value() {
+    let a: Arena<i32> = vec![1, 2, 3].into_iter().collect();
+    let b: Arena<i32> = vec![1, 2, 4].into_iter().collect();
+    let c: Arena<i32> = vec![1, 2].into_iter().collect();
+
+    // A hole at slot 1 moves the second value to slot 2, which sorts after
+    // any arena with an occupied slot 1.
+    let mut d: Arena<i32> = vec![1, 0, 0].into_iter().collect();
+    let hole = d.iter().nth(1).unwrap().0;
+    d.remove(hole);
+
+    assert!(c < a);
+    assert!(a < b);
+    assert!(b < d);
+
+    let set: BTreeSet<_> = vec![d.clone(), b.clone(), a.clone(), c.clone()]
+        .into_iter()
+        .collect();
+    let ordered: Vec<_> = set.into_iter().collect();
+    assert_eq!(ordered, vec![c, a, b, d]);
+}
+
+#[test]
+fn ordering_ignores_generations() {
+    let mut a = Arena::with_capacity(1);
+    let idx = a.insert(7);
+    a.remove(idx);
+    a.insert(5);
+
+    let mut b = Arena::with_capacity(1);
+    b.insert(5);
+
+    assert_eq!(a, b);
+    assert_eq!(a.cmp(&b), std::cmp::Ordering::Equal);
+    assert_eq!(hash_of(&a), hash_of(&b));
+}
@@ -0,0 +1,399 @@
+extern crate generational_arena_im;
+extern crate rayon;
+
+use generational_arena_im::StandardArena as Arena;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::collections::HashSet;
+use std::sync::mpsc::{channel, Sender};
+
+#[test]
+fn par_scatter_aligns_with_slots() {
+    let mut arena = Arena::with_capacity(1000);
+    let indices: Vec<_> = (0..1000).map(|i| arena.insert(i)).collect();
+    for idx in indices.iter().step_by(3) {
+        arena.remove(*idx);
+    }
+    arena.reserve(10);
+
+    let mut out = vec![Some(usize::MAX); arena.capacity() + 5];
+    arena.par_scatter(&mut out, |_, v| v * 2);
+
+    for (slot, value) in out.iter().enumerate().take(arena.capacity()) {
+        if slot < 1000 && slot % 3 != 0 {
+            assert_eq!(*value, Some(slot * 2));
+        } else {
+            assert_eq!(*value, None);
+        }
+    }
+    // Entries past the arena's capacity are left alone.
+    assert!(out[arena.capacity()..]
+        .iter()
+        .all(|v| *v == Some(usize::MAX)));
+}
+
+#[test]
+#[should_panic]
+fn par_scatter_short_buffer_panics() {
+    let arena: Arena<usize> = Arena::with_capacity(4);
+    let mut out = vec![None; 3];
+    arena.par_scatter(&mut out, |_, v| *v);
+}
+
+#[test]
+fn par_iter_mut_usize_yields_slots() {
+    use rayon::iter::ParallelIterator;
+
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..500).map(|_| arena.insert(0)).collect();
+    for idx in indices.iter().step_by(7) {
+        arena.remove(*idx);
+    }
+
+    arena.par_iter_mut_usize().for_each(|(slot, v)| *v = slot);
+    for (idx, v) in arena.iter() {
+        assert_eq!(*v, idx.to_idx());
+    }
+    assert_eq!(arena.iter().count(), arena.len());
+}
+
+#[test]
+fn par_iter_filtered_matches_sequential_filter() {
+    use rayon::iter::ParallelIterator;
+
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..5000).map(|i| arena.insert(i)).collect();
+    for idx in indices.iter().step_by(3) {
+        arena.remove(*idx);
+    }
+
+    let mut parallel: Vec<_> = arena
+        .par_iter_filtered(|v| v % 7 == 0)
+        .map(|(idx, v)| (idx, *v))
+        .collect();
+    let mut sequential: Vec<_> = arena
+        .iter()
+        .filter(|(_, v)| *v % 7 == 0)
+        .map(|(idx, v)| (idx, *v))
+        .collect();
+    parallel.sort_by_key(|(_, v)| *v);
+    sequential.sort_by_key(|(_, v)| *v);
+    assert_eq!(parallel, sequential);
+    assert_eq!(arena.par_iter_filtered(|_| false).count(), 0);
+}
+
+#[test]
+fn par_iter_filtered_splits_on_surviving_elements() {
+    use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+
+    // Every survivor is in the last tenth of the arena, so splitting by
+    // elements before filtering would leave one half with none of them.
+    let mut arena = Arena::new();
+    for i in 0..4000 {
+        arena.insert(i);
+    }
+    let pred = |v: &usize| *v >= 3600 && *v < 3800;
+    let n = arena.par_iter_filtered(pred).len();
+    assert_eq!(n, 200);
+
+    let counts: Vec<usize> = arena
+        .par_iter_filtered(pred)
+        .with_min_len(n / 2)
+        .with_max_len(n / 2)
+        .fold(|| 0, |count, _| count + 1)
+        .collect();
+    assert_eq!(counts, vec![n / 2, n / 2]);
+
+    let parallel: Vec<_> = arena
+        .par_iter_filtered(pred)
+        .map(|(i, v)| (i, *v))
+        .collect();
+    let sequential: Vec<_> = arena
+        .iter()
+        .filter(|(_, v)| pred(v))
+        .map(|(i, v)| (i, *v))
+        .collect();
+    assert_eq!(parallel, sequential);
+}
+
+#[test]
+fn par_collect_map_matches_sequential_map() {
+    use std::collections::BTreeMap;
+
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..3000).map(|i| arena.insert(i)).collect();
+    for idx in indices.iter().step_by(4) {
+        arena.remove(*idx);
+    }
+
+    let parallel = arena.par_collect_map(|v| v * 3);
+    let sequential: BTreeMap<_, _> = arena.iter().map(|(idx, v)| (idx, v * 3)).collect();
+    assert_eq!(parallel, sequential);
+}
+
+#[test]
+fn par_iter_dense_matches_sequential_iteration() {
+    use generational_arena_im::StandardSlab;
+    use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+
+    // Grown only by inserts: the dense fast path.
+    let mut slab = StandardSlab::new();
+    for i in 0..5000 {
+        slab.insert(i);
+    }
+    assert_eq!(slab.par_iter_dense().len(), slab.len());
+    let parallel: Vec<_> = slab.par_iter_dense().map(|(idx, v)| (idx, *v)).collect();
+    let sequential: Vec<_> = slab.iter().map(|(idx, v)| (idx, *v)).collect();
+    assert_eq!(parallel, sequential);
+
+    // Reserving while free slots remain leaves a hole below later inserts.
+    let mut holey = StandardSlab::with_capacity(4);
+    holey.insert(0);
+    holey.reserve(2);
+    for i in 1..4 {
+        holey.insert(i);
+    }
+    assert_eq!(holey.par_iter_dense().len(), holey.len());
+    let parallel: Vec<_> = holey.par_iter_dense().map(|(idx, v)| (idx, *v)).collect();
+    let sequential: Vec<_> = holey.iter().map(|(idx, v)| (idx, *v)).collect();
+    assert_eq!(parallel, sequential);
+}
+
+#[test]
+fn par_iter_dense_on_a_full_slab() {
+    use generational_arena_im::StandardSlab;
+    use rayon::iter::ParallelIterator;
+
+    let mut slab = StandardSlab::with_capacity(4);
+    for i in 0..4 {
+        slab.insert(i);
+    }
+    assert_eq!(slab.len(), slab.capacity());
+    let sum: usize = slab.par_iter_dense().map(|(_, v)| *v).sum();
+    assert_eq!(sum, 6);
+}
+
+#[test]
+fn par_iter_cloned_matches_sequential_clones() {
+    use rayon::iter::ParallelIterator;
+
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..2000).map(|i| arena.insert(i.to_string())).collect();
+    for idx in indices.iter().step_by(5) {
+        arena.remove(*idx);
+    }
+
+    let mut parallel: Vec<_> = arena
+        .par_iter_cloned()
+        .map(|(idx, mut s)| {
+            s.push('!');
+            (idx, s)
+        })
+        .collect();
+    let mut sequential: Vec<_> = arena
+        .iter()
+        .map(|(idx, s)| (idx, format!("{}!", s)))
+        .collect();
+    parallel.sort_by_key(|(idx, _)| idx.to_idx());
+    sequential.sort_by_key(|(idx, _)| idx.to_idx());
+    assert_eq!(parallel, sequential);
+}
+
+#[test]
+fn par_iter_balanced_splits_on_live_elements() {
+    use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+
+    // Remove almost everything in the first half, so that splitting by slots
+    // would put nearly all of the elements in the right half.
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..4000).map(|i| arena.insert(i)).collect();
+    for idx in indices
+        .iter()
+        .take(2000)
+        .filter(|idx| idx.to_idx() % 100 != 0)
+    {
+        arena.remove(*idx);
+    }
+    let n = arena.len();
+    assert_eq!(n, 2020);
+
+    let counts: Vec<usize> = arena
+        .par_iter_balanced()
+        .with_min_len(n / 2)
+        .with_max_len(n / 2)
+        .fold(|| 0, |count, _| count + 1)
+        .collect();
+    assert_eq!(counts, vec![n / 2, n / 2]);
+
+    let parallel: Vec<_> = arena.par_iter_balanced().map(|(i, v)| (i, *v)).collect();
+    let sequential: Vec<_> = arena.iter().map(|(i, v)| (i, *v)).collect();
+    assert_eq!(parallel, sequential);
+    assert_eq!(Arena::<u32>::new().par_iter_balanced().count(), 0);
+}
+
+#[test]
+fn par_iter_balanced_keeps_slots_with_leading_holes() {
+    use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..10).map(|i| arena.insert(i)).collect();
+    arena.remove(indices[0]);
+    arena.remove(indices[1]);
+    let sequential: Vec<_> = arena.iter().map(|(i, v)| (i, *v)).collect();
+
+    // Both split off an empty left half before reaching the first element.
+    let skipped: Vec<_> = arena
+        .par_iter_balanced()
+        .skip(0)
+        .map(|(i, v)| (i, *v))
+        .collect();
+    assert_eq!(skipped, sequential);
+    let fine: Vec<_> = arena
+        .par_iter_balanced()
+        .with_min_len(1)
+        .map(|(i, v)| (i, *v))
+        .collect();
+    assert_eq!(fine, sequential);
+}
+
+#[test]
+fn par_map_keeps_slots_and_generations() {
+    use generational_arena_im::Index;
+
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..3000).map(|i| arena.insert(i)).collect();
+    for idx in indices.iter().step_by(4) {
+        arena.remove(*idx);
+    }
+    arena.insert(-1);
+
+    let mapped = arena.par_map(|v| v * 3);
+    assert_eq!(mapped.len(), arena.len());
+    assert_eq!(mapped.capacity(), arena.capacity());
+    for (idx, value) in arena.iter() {
+        let mapped_idx = Index::from_raw_untyped(idx.into_raw_untyped());
+        assert_eq!(mapped.get(mapped_idx), Some(&(value * 3)));
+    }
+    assert!(arena.debug_entries().eq(mapped.debug_entries()));
+}
+
+#[test]
+fn par_indices_matches_sequential_indices() {
+    let mut arena = Arena::with_capacity(1000);
+    let indices: Vec<_> = (0..1000).map(|i| arena.insert(i)).collect();
+    for idx in indices.iter().step_by(7) {
+        arena.remove(*idx);
+    }
+    arena.insert(0);
+
+    let parallel: HashSet<_> = arena.par_indices().collect();
+    let sequential: HashSet<_> = arena.iter().map(|(idx, _)| idx).collect();
+    assert_eq!(parallel.len(), arena.len());
+    assert_eq!(parallel, sequential);
+}
+
+#[test]
+fn par_update_with_applies_field_by_slot() {
+    let mut arena = Arena::with_capacity(1000);
+    let indices: Vec<_> = (0..1000).map(|i| arena.insert(i)).collect();
+    for idx in indices.iter().step_by(5) {
+        arena.remove(*idx);
+    }
+    let field: Vec<usize> = (0..arena.capacity()).map(|slot| slot * 3).collect();
+
+    arena.par_update_with(&field, |slot, value, delta| {
+        assert_eq!(*value, slot);
+        *value += delta;
+    });
+
+    for (slot, idx) in indices.iter().enumerate() {
+        if slot % 5 == 0 {
+            assert!(!arena.contains(*idx));
+        } else {
+            assert_eq!(arena[*idx], slot * 4);
+        }
+    }
+}
+
+#[test]
+fn par_count_matches_sequential_count() {
+    let mut arena = Arena::with_capacity(1000);
+    let indices: Vec<_> = (0..1000).map(|i| arena.insert(i)).collect();
+    for idx in indices.iter().step_by(4) {
+        arena.remove(*idx);
+    }
+
+    let matches = |v: &usize| v % 3 == 1;
+    let sequential = arena.iter().filter(|(_, v)| matches(v)).count();
+    assert_eq!(arena.par_count(matches), sequential);
+    assert_eq!(arena.par_count(|_| true), arena.len());
+}
+
+/// A per-worker running total which reports to `sender` when dropped.
+struct Accumulator {
+    total: usize,
+    count: usize,
+    sender: Sender<(usize, usize)>,
+}
+
+impl Clone for Accumulator {
+    fn clone(&self) -> Self {
+        Accumulator {
+            total: 0,
+            count: 0,
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl Drop for Accumulator {
+    fn drop(&mut self) {
+        self.sender.send((self.total, self.count)).unwrap();
+    }
+}
+
+#[test]
+fn par_for_each_with_sums_into_worker_accumulators() {
+    let mut arena = Arena::with_capacity(10_000);
+    let indices: Vec<_> = (0..10_000).map(|i| arena.insert(i)).collect();
+    for idx in indices.iter().step_by(5) {
+        arena.remove(*idx);
+    }
+
+    let (sender, receiver) = channel();
+    let init = Accumulator {
+        total: 0,
+        count: 0,
+        sender,
+    };
+    arena.par_for_each_with(init, |acc, (idx, v)| {
+        assert_eq!(arena[idx], *v);
+        acc.total += v;
+        acc.count += 1;
+    });
+
+    let (total, count) = receiver
+        .iter()
+        .fold((0, 0), |(total, count), (t, c)| (total + t, count + c));
+    assert_eq!(total, arena.iter().map(|(_, v)| v).sum::<usize>());
+    assert_eq!(count, arena.len());
+}
+
+#[test]
+fn par_insert_ordered_keeps_source_order() {
+    let mut arena = Arena::with_capacity(8);
+    let indices: Vec<_> = (0..8).map(|i| arena.insert(i)).collect();
+    arena.remove(indices[2]);
+    let capacity = arena.capacity();
+
+    let source: Vec<usize> = (0..10_000).collect();
+    let inserted = arena.par_insert_ordered(source.clone().into_par_iter().map(|i| i * 3));
+    assert_eq!(inserted.len(), source.len());
+    for (position, idx) in inserted.iter().enumerate() {
+        assert_eq!(idx.to_idx(), capacity + position);
+        assert_eq!(arena[*idx], source[position] * 3);
+    }
+    assert_eq!(arena.len(), 7 + source.len());
+
+    // The hole left by the removal is still available afterwards.
+    assert_eq!(arena.insert(1).to_idx(), 2);
+}
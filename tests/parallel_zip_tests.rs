@@ -16,7 +16,7 @@ fn par_zip_test() {
         arena_b.insert(i * 2);
     }
 
-    let seq: Vec<_> = (0..100u32).into_iter().map(|v| v * 3).collect();
+    let seq: Vec<_> = (0..100u32).map(|v| v * 3).collect();
 
     let par: Vec<_> = arena_a
         .par_iter()
@@ -37,7 +37,7 @@ fn par_mut_zip_test() {
         arena_b.insert(i);
     }
 
-    let seq: Vec<_> = (0..100i32).into_iter().map(|v| v).collect();
+    let seq: Vec<_> = (0..100i32).collect();
 
     arena_a
         .par_iter_mut()
@@ -87,38 +87,38 @@ fn test_multiple_arena_operations() {
     assert_eq!(vec.len(), 4950); // Sum of first 100 natural numbers multiplied by 2
 }
 
-/// Zipping two arenas where one has holes should still produce exactly
-/// N pairs (no more, no fewer).
-// #[test]
-// fn par_iter_zip_with_holes_len_matches() {
-//     let mut arena_1 = Arena::new();
-//     let mut arena_2 = Arena::new();
-
-//     // A: insert 6, remove 2 in the middle → 4 occupied
-//     let a0 = arena_1.insert(10);
-//     let _a1 = arena_1.insert(11);
-//     let a2 = arena_1.insert(12);
-//     let _a3 = arena_1.insert(13);
-//     let _a4 = arena_1.insert(14);
-//     let _a5 = arena_1.insert(15);
-//     arena_1.remove(a2);
-//     arena_1.remove(a0);
-
-//     // B: 4 inserts, no removes
-//     for i in 0..4 {
-//         arena_2.insert(i);
-//     }
-
-//     // zip should give exactly 4 pairs
-//     let pairs: Vec<_> = arena_1.par_iter().zip(arena_2.par_iter()).collect();
-
-//     assert_eq!(
-//         pairs.len(),
-//         4,
-//         "expected 4 zipped entries, got {}",
-//         pairs.len()
-//     );
-// }
+// Zipping two arenas where one has holes should still produce exactly
+// N pairs (no more, no fewer).
+#[test]
+fn par_iter_zip_with_holes_len_matches() {
+    let mut arena_1 = Arena::new();
+    let mut arena_2 = Arena::new();
+
+    // A: insert 6, remove 2 in the middle → 4 occupied
+    let a0 = arena_1.insert(10);
+    let _a1 = arena_1.insert(11);
+    let a2 = arena_1.insert(12);
+    let _a3 = arena_1.insert(13);
+    let _a4 = arena_1.insert(14);
+    let _a5 = arena_1.insert(15);
+    arena_1.remove(a2);
+    arena_1.remove(a0);
+
+    // B: 4 inserts, no removes
+    for i in 0..4 {
+        arena_2.insert(i);
+    }
+
+    // zip should give exactly 4 pairs
+    let pairs: Vec<_> = arena_1.par_iter().zip(arena_2.par_iter()).collect();
+
+    assert_eq!(
+        pairs.len(),
+        4,
+        "expected 4 zipped entries, got {}",
+        pairs.len()
+    );
+}
 
 /// par_iter_mut + for_each should touch exactly the occupied slots, no more.
 #[test]
@@ -157,7 +157,7 @@ fn par_mut_zip_test_with_removals() {
         arena_b.remove(b2);
     }
 
-    let seq: Vec<_> = (0..100i32).into_iter().map(|v| v * 3).collect();
+    let seq: Vec<_> = (0..100i32).map(|v| v * 3).collect();
 
     arena_a
         .par_iter_mut()
@@ -206,7 +206,7 @@ fn par_into_iter_test_with_removals() {
 
     let par: Vec<_> = arena_a.iter().map(|(_, a)| *a).collect();
 
-    let seq: Vec<_> = (0..N).into_iter().map(|x| (x * 3) + (x + 3)).collect();
+    let seq: Vec<_> = (0..N).map(|x| (x * 3) + (x + 3)).collect();
 
     assert_eq!(seq, par);
 }
@@ -244,7 +244,6 @@ fn par_into_iter_test_with_unbalanced_removals() {
     let par: Vec<_> = arena_a.iter().map(|(_, a)| *a).collect();
 
     let seq: Vec<_> = (0..N)
-        .into_iter()
         .filter_map(|x| {
             if x > N / 3 {
                 None // skip the section where removals happened
@@ -257,27 +256,57 @@ fn par_into_iter_test_with_unbalanced_removals() {
     assert_eq!(seq, par);
 }
 
-// #[test]
-// #[should_panic(expected = "too many values pushed to consumer")]
-// fn par_zip_panics_on_mismatched_lengths() {
-//     const N: usize = 30_000;
-//     let mut arena_a = Arena::new();
-//     let mut arena_b = Arena::new();
-
-//     let handles_a: Vec<_> = (0..N).map(|i| arena_a.insert(i)).collect();
-//     let handles_b: Vec<_> = (0..N).map(|i| arena_b.insert(i)).collect();
-
-//     // Remove elements in *different* patterns so the two
-//     // `ParIter`s no longer have the same real length.
-//     for h in handles_a.iter().step_by(2) {
-//         arena_a.remove(*h);
-//     } // every 2nd
-//     for h in handles_b.iter().step_by(3) {
-//         arena_b.remove(*h);
-//     } // every 3rd
-
-//     // Each iterator lies about its length in a *different* way,
-//     // so `zip()` asks one side to split at an impossible point.
-//     let _collected: Vec<_> = arena_a.par_iter().zip(arena_b.par_iter()).collect();
-//     // should panic
-// }
+// Zipping two arenas whose holes follow different patterns pairs up their
+// elements in order and stops at the end of the shorter one.
+#[test]
+fn par_zip_with_mismatched_lengths() {
+    const N: usize = 30_000;
+    let mut arena_a = Arena::new();
+    let mut arena_b = Arena::new();
+
+    let handles_a: Vec<_> = (0..N).map(|i| arena_a.insert(i)).collect();
+    let handles_b: Vec<_> = (0..N).map(|i| arena_b.insert(i)).collect();
+
+    // Remove elements in *different* patterns so the two
+    // `ParIter`s no longer have the same length.
+    for h in handles_a.iter().step_by(2) {
+        arena_a.remove(*h);
+    } // every 2nd
+    for h in handles_b.iter().step_by(3) {
+        arena_b.remove(*h);
+    } // every 3rd
+
+    let par: Vec<_> = arena_a
+        .par_iter()
+        .zip(arena_b.par_iter())
+        .map(|((_, a), (_, b))| (*a, *b))
+        .collect();
+    let seq: Vec<_> = arena_a
+        .iter()
+        .zip(arena_b.iter())
+        .map(|((_, a), (_, b))| (*a, *b))
+        .collect();
+    assert_eq!(par.len(), N / 2);
+    assert_eq!(par, seq);
+}
+
+#[test]
+fn par_zip_live_truncates_to_shorter_arena() {
+    let mut arena_a = Arena::new();
+    let mut arena_b = Arena::new();
+
+    // A: 6 inserts, 2 removes → 4 live, with holes at the front and middle
+    let handles_a: Vec<_> = (0..6).map(|i| arena_a.insert(i)).collect();
+    arena_a.remove(handles_a[0]);
+    arena_a.remove(handles_a[2]);
+
+    // B: 7 inserts, 1 remove → 6 live
+    let handles_b: Vec<_> = (0..7).map(|i| arena_b.insert(i * 10)).collect();
+    arena_b.remove(handles_b[3]);
+
+    let pairs: Vec<_> = arena_a
+        .par_zip_live(&arena_b)
+        .map(|((_, a), (_, b))| (*a, *b))
+        .collect();
+    assert_eq!(pairs, vec![(1, 0), (3, 10), (4, 20), (5, 40)]);
+}
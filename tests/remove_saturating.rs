@@ -0,0 +1,34 @@
+extern crate generational_arena_im;
+use generational_arena_im::{EntryKind, TinyArena};
+
+#[test]
+fn remove_saturating_retires_slot_at_max_generation() {
+    let mut arena = TinyArena::with_capacity(1);
+    // Burn through every generation but the last one.
+    for i in 0..(u16::MAX - 1) as usize {
+        let idx = arena.insert(i);
+        assert_eq!(arena.remove_saturating(idx), Some(i));
+    }
+    assert_eq!(arena.capacity(), 1);
+
+    let last = arena.insert(1);
+    assert_eq!(last.gen().to_usize(), u16::MAX as usize);
+    assert_eq!(arena.remove_saturating(last), Some(1));
+    assert!(arena.is_empty());
+    assert!(!arena.contains(last));
+    let retired = last.arr_idx() as usize;
+    assert_eq!(
+        arena.debug_entries().nth(retired),
+        Some((retired, EntryKind::Retired))
+    );
+    assert_eq!(arena.repair_free_list(), 0);
+
+    // The retired slot is never handed out again, so `last` stays stale.
+    for i in 0..10 {
+        let idx = arena.insert(i);
+        assert_ne!(idx.arr_idx(), last.arr_idx());
+        assert!(!arena.contains(last));
+        assert_eq!(arena[idx], i);
+    }
+    assert_eq!(arena.remove_saturating(last), None);
+}
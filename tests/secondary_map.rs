@@ -0,0 +1,83 @@
+extern crate generational_arena_im;
+use generational_arena_im::{StandardArena, StandardSecondaryMap};
+
+#[derive(Clone, Debug, PartialEq)]
+struct Name(&'static str);
+
+#[derive(Clone, Debug, PartialEq)]
+struct Position(i32, i32);
+
+#[test]
+fn secondary_map_follows_primary_handles() {
+    let mut names = StandardArena::new();
+    let mut positions = StandardSecondaryMap::new();
+
+    let a = names.insert(Name("a"));
+    let b = names.insert(Name("b"));
+    let c = names.insert(Name("c"));
+    positions.insert(a, Position(0, 0));
+    positions.insert(c, Position(2, 2));
+
+    assert_eq!(positions.len(), 2);
+    assert_eq!(positions.get(a), Some(&Position(0, 0)));
+    assert_eq!(positions.get(b), None);
+    assert_eq!(positions.get(c), Some(&Position(2, 2)));
+
+    positions.get_mut(c).unwrap().0 = 3;
+    assert_eq!(positions[c], Position(3, 2));
+
+    let pairs: Vec<_> = positions
+        .iter::<Name>()
+        .map(|(idx, pos)| (names[idx].clone(), pos.clone()))
+        .collect();
+    assert_eq!(
+        pairs,
+        vec![(Name("a"), Position(0, 0)), (Name("c"), Position(3, 2))]
+    );
+}
+
+#[test]
+fn secondary_map_rejects_stale_handles() {
+    let mut names = StandardArena::with_capacity(1);
+    let mut positions = StandardSecondaryMap::new();
+
+    let a = names.insert(Name("a"));
+    positions.insert(a, Position(1, 1));
+    names.remove(a);
+    let b = names.insert(Name("b"));
+    assert_eq!(a.arr_idx(), b.arr_idx());
+
+    assert!(positions.get(b).is_none());
+    assert_eq!(positions.remove(b), None);
+    assert_eq!(positions.insert(b, Position(2, 2)), None);
+    assert!(positions.get(a).is_none());
+    assert_eq!(positions.len(), 1);
+
+    assert_eq!(positions.remove(b), Some(Position(2, 2)));
+    assert!(positions.is_empty());
+}
+
+#[test]
+fn secondary_map_insert_ignores_older_generations() {
+    let mut names = StandardArena::new();
+    let mut positions = StandardSecondaryMap::new();
+
+    let old = names.insert(Name("old"));
+    names.remove(old);
+    let new = names.insert(Name("new"));
+    assert_eq!(old.arr_idx(), new.arr_idx());
+
+    assert_eq!(positions.insert(new, Position(1, 1)), None);
+    assert_eq!(positions.insert(old, Position(0, 0)), None);
+    assert_eq!(positions.get(new), Some(&Position(1, 1)));
+    assert_eq!(positions.get(old), None);
+    assert_eq!(positions.len(), 1);
+
+    // A newer generation still replaces the value of an older one.
+    names.remove(new);
+    let newest = names.insert(Name("newest"));
+    assert_eq!(positions.insert(newest, Position(2, 2)), None);
+    assert_eq!(positions.get(new), None);
+    assert_eq!(positions.get(newest), Some(&Position(2, 2)));
+    assert_eq!(positions.len(), 1);
+}
@@ -0,0 +1,26 @@
+#![cfg(feature = "serde")]
+
+extern crate generational_arena_im;
+extern crate serde_json;
+
+use generational_arena_im::{ArenaIndex, NonZeroIndex, PtrSlab};
+
+#[test]
+fn nonzero_index_round_trips() {
+    let mut slab = PtrSlab::new();
+    let idx = slab.insert('a');
+    let first = idx.arr_idx();
+
+    for index in [first, NonZeroIndex::<usize>::from_idx(usize::MAX - 1)] {
+        let json = serde_json::to_string(&index).unwrap();
+        let back: NonZeroIndex<usize> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, index);
+    }
+    assert_eq!(serde_json::to_string(&first).unwrap(), "1");
+}
+
+#[test]
+fn nonzero_index_rejects_zero() {
+    let err = serde_json::from_str::<NonZeroIndex<usize>>("0").unwrap_err();
+    assert!(err.to_string().contains("nonzero"));
+}
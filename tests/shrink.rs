@@ -0,0 +1,114 @@
+extern crate generational_arena_im;
+use generational_arena_im::StandardArena as Arena;
+
+#[test]
+fn shrink_to_trims_trailing_free_slots() {
+    let mut arena = Arena::with_capacity(32);
+    let indices: Vec<_> = (0..32).map(|i| arena.insert(i)).collect();
+    assert_eq!(arena.capacity(), 32);
+
+    for idx in &indices[4..] {
+        arena.remove(*idx);
+    }
+    arena.shrink_to(16);
+    assert_eq!(arena.capacity(), 16);
+    assert_eq!(arena.len(), 4);
+    for (i, idx) in indices[..4].iter().enumerate() {
+        assert_eq!(arena[*idx], i);
+    }
+
+    // The free list only covers the remaining slots.
+    for i in 0..12 {
+        arena.try_insert(100 + i).unwrap();
+    }
+    assert!(arena.try_insert(0).is_err());
+}
+
+#[test]
+fn shrink_to_keeps_highest_occupied_slot() {
+    let mut arena = Arena::with_capacity(8);
+    let indices: Vec<_> = (0..8).map(|i| arena.insert(i)).collect();
+    for idx in &indices[..6] {
+        arena.remove(*idx);
+    }
+    arena.shrink_to(0);
+    assert_eq!(arena.capacity(), 8);
+    assert_eq!(arena[indices[7]], 7);
+}
+
+#[test]
+fn shrink_to_above_capacity_is_noop() {
+    let mut arena: Arena<usize> = Arena::with_capacity(8);
+    arena.shrink_to(16);
+    assert_eq!(arena.capacity(), 8);
+}
+
+#[test]
+fn shrink_to_empty_then_insert() {
+    let mut arena = Arena::with_capacity(8);
+    let idx = arena.insert(1);
+    arena.remove(idx);
+    arena.shrink_to(0);
+    assert_eq!(arena.capacity(), 0);
+    let idx = arena.insert(2);
+    assert_eq!(arena[idx], 2);
+}
+
+#[test]
+fn remove_and_trim_keeps_a_stack_tight() {
+    let mut arena = Arena::with_capacity(1);
+    let mut stack = Vec::new();
+    for round in 0..20 {
+        for i in 0..8 {
+            stack.push(arena.insert(round * 8 + i));
+        }
+        while stack.len() > 2 {
+            let top = stack.pop().unwrap();
+            assert!(arena.remove_and_trim(top).is_some());
+            assert_eq!(arena.capacity(), stack.len());
+        }
+    }
+    for (n, idx) in stack.iter().enumerate() {
+        assert!(arena.contains(*idx), "element {} lost", n);
+    }
+}
+
+#[test]
+fn remove_and_trim_in_the_middle_keeps_capacity() {
+    let mut arena = Arena::with_capacity(3);
+    let a = arena.insert(1);
+    let b = arena.insert(2);
+    let c = arena.insert(3);
+
+    assert_eq!(arena.remove_and_trim(a), Some(1));
+    assert_eq!(arena.capacity(), 3);
+    assert_eq!(arena.remove_and_trim(a), None);
+
+    // Removing the last element releases every trailing free slot.
+    assert_eq!(arena.remove_and_trim(c), Some(3));
+    assert_eq!(arena.capacity(), 2);
+    assert_eq!(arena.remove_and_trim(b), Some(2));
+    assert_eq!(arena.capacity(), 0);
+    let d = arena.insert(4);
+    assert_eq!(arena[d], 4);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn reserve_and_touch_adds_usable_free_slots() {
+    let mut arena = Arena::with_capacity(2);
+    let a = arena.insert(1);
+    arena.insert(2);
+    arena.remove(a);
+
+    arena.reserve_and_touch(1000);
+    assert_eq!(arena.capacity(), 1002);
+    assert_eq!(arena.len(), 1);
+    assert_eq!(arena.free_count(), 1001);
+
+    let inserted: Vec<_> = (0..1001).map(|i| arena.insert(i)).collect();
+    assert_eq!(arena.capacity(), 1002);
+    for (i, idx) in inserted.iter().enumerate() {
+        assert_eq!(arena[*idx], i);
+    }
+}
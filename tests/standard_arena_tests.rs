@@ -1,6 +1,8 @@
 extern crate generational_arena_im;
-use generational_arena_im::StandardArena as Arena;
-use std::collections::BTreeSet;
+use generational_arena_im::{
+    DisjointError, LookupError, RelocateError, StandardArena as Arena, StandardIndex as Index,
+};
+use std::collections::{BTreeSet, HashMap};
 
 #[test]
 fn can_get_live_value() {
@@ -84,7 +86,7 @@ fn index_deleted_item() {
     let mut arena = Arena::new();
     let idx = arena.insert(42);
     arena.remove(idx);
-    arena[idx];
+    let _ = arena[idx];
 }
 
 #[test]
@@ -174,3 +176,999 @@ fn retain() {
     assert_eq!(arena[c], 3);
     assert_eq!(arena.len(), 2);
 }
+
+#[test]
+fn into_iter_indexed() {
+    let mut arena = Arena::new();
+    let a = arena.insert("a");
+    let b = arena.insert("b");
+    let c = arena.insert("c");
+    let pairs: Vec<_> = arena.into_iter_indexed().collect();
+    assert_eq!(pairs, vec![(a, "a"), (b, "b"), (c, "c")]);
+}
+
+#[test]
+fn remove_if() {
+    let mut arena = Arena::with_capacity(1);
+    let idx = arena.insert(5);
+
+    assert_eq!(arena.remove_if(idx, |v| *v == 6), None);
+    assert_eq!(arena[idx], 5);
+
+    // A failed predicate must not bump the generation: a remove-then-insert
+    // cycle still produces the same generation an untouched arena would.
+    let mut fresh = Arena::with_capacity(1);
+    let fresh_idx = fresh.insert(5);
+    fresh.remove(fresh_idx);
+    assert_eq!(arena.remove_if(idx, |v| *v == 5), Some(5));
+    assert_eq!(arena.insert(0).gen(), fresh.insert(0).gen());
+}
+
+#[test]
+fn retain_map() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..10).map(|i| arena.insert(i)).collect();
+
+    let removed = arena.retain_map(|_, v| if v % 2 == 0 { Some(v * 2) } else { None });
+    assert_eq!(removed, 5);
+    assert_eq!(arena.len(), 5);
+    for (i, idx) in indices.iter().enumerate() {
+        if i % 2 == 0 {
+            assert_eq!(arena[*idx], i * 2);
+        } else {
+            assert!(!arena.contains(*idx));
+        }
+    }
+
+    // Freed slots are reused with fresh generations.
+    let new = arena.insert(100);
+    assert!(indices.iter().all(|idx| *idx != new));
+}
+
+#[test]
+fn free_count_complements_len() {
+    let mut arena = Arena::with_capacity(2);
+    let mut live = Vec::new();
+    for round in 0..50 {
+        live.push(arena.insert(round));
+        if round % 3 == 2 {
+            arena.remove(live.remove(round % live.len()));
+        }
+        assert_eq!(arena.len() + arena.free_count(), arena.capacity());
+    }
+    for idx in live {
+        arena.remove(idx);
+        assert_eq!(arena.len() + arena.free_count(), arena.capacity());
+    }
+    assert_eq!(arena.free_count(), arena.capacity());
+}
+
+#[test]
+fn truncate_live_keeps_lowest_slots() {
+    let mut arena = Arena::with_capacity(12);
+    let indices: Vec<_> = (0..10).map(|i| arena.insert(i)).collect();
+    let capacity = arena.capacity();
+
+    arena.truncate_live(4);
+    assert_eq!(arena.len(), 4);
+    assert_eq!(arena.capacity(), capacity);
+    for (i, idx) in indices.iter().enumerate() {
+        assert_eq!(arena.contains(*idx), i < 4);
+    }
+
+    arena.truncate_live(10);
+    assert_eq!(arena.len(), 4);
+}
+
+#[test]
+fn resize_with_fills_to_target_len() {
+    let mut arena = Arena::new();
+    let mut next = 0;
+    arena.resize_with(5, || {
+        next += 1;
+        next * 10
+    });
+    assert_eq!(arena.len(), 5);
+    let mut values: Vec<_> = arena.iter().map(|(_, v)| *v).collect();
+    values.sort();
+    assert_eq!(values, vec![10, 20, 30, 40, 50]);
+
+    arena.resize_with(2, || unreachable!());
+    assert_eq!(arena.len(), 5);
+}
+
+#[test]
+fn fill_with_occupies_all_capacity() {
+    let mut arena = Arena::with_capacity(8);
+    let mut calls = 0;
+    arena.fill_with(|| {
+        calls += 1;
+        calls
+    });
+    assert_eq!(calls, 8);
+    assert_eq!(arena.len(), arena.capacity());
+    assert_eq!(arena.capacity(), 8);
+
+    arena.fill_with(|| unreachable!());
+
+    let mut limited = Arena::with_capacity(8);
+    limited.set_max_len(3);
+    limited.fill_with(|| 0);
+    assert_eq!(limited.len(), 3);
+}
+
+#[test]
+fn contains_value_and_find() {
+    let mut arena = Arena::with_capacity(4);
+    let indices: Vec<_> = (0..4).map(|i| arena.insert(i * 10)).collect();
+    arena.remove(indices[1]);
+
+    assert!(arena.contains_value(&20));
+    assert!(!arena.contains_value(&10));
+    assert!(!arena.contains_value(&5));
+
+    // The first match in slot order wins.
+    assert_eq!(arena.find(|v| *v >= 10), Some((indices[2], &20)));
+    assert_eq!(arena.find(|v| *v > 100), None);
+}
+
+#[test]
+fn find_map_short_circuits() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..10).map(|i| arena.insert(i)).collect();
+    arena.remove(indices[0]);
+
+    let mut calls = 0;
+    let found = arena.find_map(|idx, v| {
+        calls += 1;
+        if *v >= 3 {
+            Some(idx)
+        } else {
+            None
+        }
+    });
+    assert_eq!(found, Some(indices[3]));
+    assert_eq!(calls, 3);
+
+    assert_eq!(
+        arena.find_map(|_, v| if *v > 100 { Some(*v) } else { None }),
+        None
+    );
+}
+
+#[test]
+fn index_of_recovers_handle_from_reference() {
+    let mut arena = Arena::with_capacity(4);
+    let indices: Vec<_> = (0..100).map(|i| arena.insert(i % 3)).collect();
+
+    for idx in &indices {
+        let value = arena.get(*idx).unwrap();
+        assert_eq!(arena.index_of(value), Some(*idx));
+    }
+
+    // Equal values elsewhere are not the same element.
+    let copy = arena[indices[0]];
+    assert_eq!(arena.index_of(&copy), None);
+
+    // A clone shares its storage, so its references are recognized too.
+    let clone = arena.clone();
+    assert_eq!(arena.index_of(&clone[indices[5]]), Some(indices[5]));
+}
+
+#[test]
+fn insert_unique_interns_equal_values() {
+    let mut strings = Arena::new();
+    let hello = strings.insert_unique(String::from("hello"));
+    let world = strings.insert_unique(String::from("world"));
+    assert_ne!(hello, world);
+    assert_eq!(strings.insert_unique(String::from("hello")), hello);
+    assert_eq!(strings.len(), 2);
+
+    // Once removed, an equal value gets a fresh handle.
+    strings.remove(hello);
+    let again = strings.insert_unique(String::from("hello"));
+    assert_ne!(again, hello);
+    assert_eq!(strings[again], "hello");
+}
+
+#[test]
+fn iter_rev_descends_by_slot() {
+    let mut arena = Arena::with_capacity(8);
+    let indices: Vec<_> = (0..8).map(|i| arena.insert(i)).collect();
+    arena.remove(indices[2]);
+    arena.remove(indices[5]);
+    arena.insert(100);
+
+    let slots: Vec<_> = arena.iter_rev().map(|(idx, _)| idx.to_idx()).collect();
+    assert!(slots.windows(2).all(|w| w[0] > w[1]));
+    assert_eq!(slots.len(), arena.len());
+
+    let reversed: Vec<_> = arena.iter().rev().collect();
+    assert_eq!(arena.iter_rev().collect::<Vec<_>>(), reversed);
+}
+
+#[test]
+fn to_columns_keeps_columns_aligned() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..6).map(|i| arena.insert((i, i * 10))).collect();
+    arena.remove(indices[1]);
+    arena.remove(indices[4]);
+
+    let (xs, ys) = arena.to_columns(|&(x, y)| (x, y));
+    assert_eq!(xs, vec![0, 2, 3, 5]);
+    assert_eq!(ys, vec![0, 20, 30, 50]);
+    for ((idx, &(x, y)), (cx, cy)) in arena.iter().zip(xs.iter().zip(ys.iter())) {
+        assert_eq!((x, y), (*cx, *cy));
+        assert_eq!(arena[idx], (*cx, *cy));
+    }
+}
+
+#[test]
+fn has_free_and_first_free() {
+    let mut arena = Arena::with_capacity(3);
+    let indices: Vec<_> = (0..3)
+        .map(|i| {
+            let next = arena.first_free();
+            let idx = arena.insert(i);
+            assert_eq!(next, Some(idx.to_idx()));
+            idx
+        })
+        .collect();
+    assert!(!arena.has_free());
+    assert_eq!(arena.first_free(), None);
+
+    arena.remove(indices[1]);
+    assert!(arena.has_free());
+    assert_eq!(arena.first_free(), Some(1));
+    assert_eq!(arena.try_insert(7).unwrap().to_idx(), 1);
+}
+
+#[test]
+fn swap_contents_exchanges_everything() {
+    let mut front = Arena::with_capacity(2);
+    let mut back = Arena::with_capacity(8);
+    back.set_max_len(5);
+    let front_indices: Vec<_> = (0..2).map(|i| front.insert(i)).collect();
+    let back_indices: Vec<_> = (10..13).map(|i| back.insert(i)).collect();
+    back.remove(back_indices[1]);
+
+    front.swap_contents(&mut back);
+    assert_eq!(front.capacity(), 8);
+    assert_eq!(back.capacity(), 2);
+    assert_eq!(front.max_len(), Some(5));
+    assert_eq!(back.max_len(), None);
+    assert_eq!(front.len(), 2);
+    assert_eq!(front[back_indices[0]], 10);
+    assert!(!front.contains(back_indices[1]));
+    assert_eq!(front[back_indices[2]], 12);
+    for (i, idx) in front_indices.iter().enumerate() {
+        assert_eq!(back[*idx], i);
+    }
+}
+
+#[test]
+fn next_after_and_prev_before_skip_free_slots() {
+    let mut arena = Arena::with_capacity(8);
+    let indices: Vec<_> = (0..8).map(|i| arena.insert(i)).collect();
+    for &slot in &[0, 2, 3, 6, 7] {
+        arena.remove(indices[slot]);
+    }
+
+    let mut forward = vec![];
+    let mut cursor = indices[0];
+    while let Some((idx, value)) = arena.next_after(cursor) {
+        forward.push(*value);
+        cursor = idx;
+    }
+    assert_eq!(forward, vec![1, 4, 5]);
+
+    let mut backward = vec![];
+    let mut cursor = indices[7];
+    while let Some((idx, value)) = arena.prev_before(cursor) {
+        backward.push(*value);
+        cursor = idx;
+    }
+    assert_eq!(backward, vec![5, 4, 1]);
+}
+
+#[test]
+fn split_live_mut_across_threads() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..100).map(|i| arena.insert(i)).collect();
+    for idx in indices.iter().take(40).step_by(2) {
+        arena.remove(*idx);
+    }
+
+    let parts = arena.split_live_mut(3);
+    assert_eq!(parts.len(), 3);
+    let counts: Vec<usize> = std::thread::scope(|scope| {
+        let handles: Vec<_> = parts
+            .into_iter()
+            .map(|part| scope.spawn(move || part.map(|(_, value)| *value += 1000).count()))
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    assert_eq!(counts, vec![26, 27, 27]);
+
+    for (i, idx) in indices.iter().enumerate() {
+        let removed = i < 40 && i % 2 == 0;
+        assert_eq!(
+            arena.get(*idx).copied(),
+            if removed { None } else { Some(i + 1000) }
+        );
+    }
+    assert_eq!(Arena::<u32>::new().split_live_mut(4).len(), 0);
+}
+
+#[test]
+fn get_cloned_and_get_or() {
+    let mut arena = Arena::new();
+    let hit = arena.insert(String::from("hit"));
+    let miss = arena.insert(String::from("miss"));
+    arena.remove(miss);
+
+    assert_eq!(arena.get_cloned(hit), Some(String::from("hit")));
+    assert_eq!(arena.get_cloned(miss), None);
+    assert_eq!(arena.get_or(hit, String::from("fallback")), "hit");
+    assert_eq!(arena.get_or(miss, String::from("fallback")), "fallback");
+}
+
+#[test]
+fn iter_since_yields_elements_inserted_after_recording() {
+    let mut arena = Arena::new();
+    let old: Vec<_> = (0..5).map(|i| arena.insert(i)).collect();
+    arena.remove(old[1]);
+    let since = arena.generation();
+
+    let new: Vec<_> = (10..13).map(|i| arena.insert(i)).collect();
+    arena.remove(new[2]);
+    let newer = arena.insert(20);
+
+    let mut yielded: Vec<_> = arena.iter_since(since).map(|(idx, _)| idx).collect();
+    yielded.sort_by_key(|idx| idx.to_idx());
+    let mut expected = vec![new[0], new[1], newer];
+    expected.sort_by_key(|idx| idx.to_idx());
+    assert_eq!(yielded, expected);
+}
+
+#[test]
+fn all_live_and_first_stale() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..4).map(|i| arena.insert(i)).collect();
+    assert!(arena.all_live(&indices));
+    assert_eq!(arena.first_stale(&indices), None);
+
+    arena.remove(indices[2]);
+    arena.insert(5);
+    assert!(!arena.all_live(&indices));
+    assert_eq!(arena.first_stale(&indices), Some(indices[2]));
+
+    assert!(arena.all_live(&[]));
+    assert_eq!(arena.first_stale(&[]), None);
+}
+
+#[test]
+fn reserve_range_reports_new_free_slots() {
+    use generational_arena_im::EntryKind;
+
+    let mut arena = Arena::<u32>::with_capacity(4);
+    let range = arena.reserve_range(3);
+    assert_eq!(range, 4..7);
+    for (slot, kind) in arena.debug_entries().skip(4) {
+        assert!(range.contains(&slot));
+        assert!(
+            matches!(kind, EntryKind::Free { .. }),
+            "slot {} is occupied",
+            slot
+        );
+    }
+    assert_eq!(arena.reserve_range(0), 7..7);
+}
+
+#[test]
+fn reserve_sparse_plans_for_peak() {
+    let mut arena = Arena::<u32>::new();
+    arena.reserve_sparse(100, 0.1);
+    assert!((1000..=1001).contains(&arena.capacity()));
+
+    // Existing elements are kept on top of the peak, and spare capacity
+    // counts towards it.
+    let mut arena = Arena::with_capacity(50);
+    for i in 0..10 {
+        arena.insert(i);
+    }
+    arena.reserve_sparse(20, 0.5);
+    assert_eq!(arena.capacity(), 50);
+    arena.reserve_sparse(30, 0.5);
+    assert_eq!(arena.capacity(), 70);
+}
+
+#[test]
+#[should_panic(expected = "density must be in (0, 1]")]
+fn reserve_sparse_rejects_zero_density() {
+    Arena::<u32>::new().reserve_sparse(10, 0.0);
+}
+
+#[test]
+fn iter_physical_slots_match_indices() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..10).map(|i| arena.insert(i)).collect();
+    for idx in indices.iter().step_by(3) {
+        arena.remove(*idx);
+    }
+
+    let physical: Vec<_> = arena.iter_physical().collect();
+    assert_eq!(physical.len(), arena.len());
+    for (slot, idx, value) in physical {
+        assert_eq!(slot, idx.to_idx());
+        assert_eq!(arena.get(idx), Some(value));
+    }
+}
+
+#[test]
+fn iter_with_slice_aligns_metadata_by_slot() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..10).map(|i| arena.insert(i)).collect();
+    for idx in indices.iter().step_by(4) {
+        arena.remove(*idx);
+    }
+    let meta: Vec<String> = (0..arena.capacity())
+        .map(|slot| format!("slot {}", slot))
+        .collect();
+
+    let paired: Vec<_> = arena.iter_with_slice(&meta).collect();
+    assert_eq!(paired.len(), arena.len());
+    for (idx, value, m) in paired {
+        assert_eq!(arena[idx], *value);
+        assert_eq!(*m, format!("slot {}", value));
+    }
+}
+
+#[test]
+#[should_panic(expected = "shorter than the arena's capacity")]
+fn iter_with_slice_panics_on_short_slice() {
+    let arena = Arena::<u32>::with_capacity(4);
+    let _ = arena.iter_with_slice(&[0; 3]);
+}
+
+#[test]
+fn clone_filtered_leaves_original_untouched() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..10).map(|i| arena.insert(i)).collect();
+    arena.remove(indices[4]);
+    let before: Vec<_> = arena.iter().map(|(idx, v)| (idx, *v)).collect();
+
+    let evens = arena.clone_filtered(|_, v| v % 2 == 0);
+    let values: Vec<_> = evens.iter().map(|(_, v)| *v).collect();
+    assert_eq!(values, vec![0, 2, 6, 8]);
+    assert_eq!(evens.len(), 4);
+
+    let after: Vec<_> = arena.iter().map(|(idx, v)| (idx, *v)).collect();
+    assert_eq!(before, after);
+}
+
+#[test]
+fn min_generation_bits_covers_the_newest_generation() {
+    let mut arena = Arena::new();
+    assert_eq!(arena.min_generation_bits(), 0);
+
+    let keep = arena.insert(0);
+    for i in 0..4 {
+        let idx = arena.insert(i);
+        arena.remove(idx);
+    }
+    let newest = arena.insert(5);
+    assert_eq!(keep.gen().to_usize(), 1);
+    assert_eq!(newest.gen().to_usize(), 5);
+    assert_eq!(arena.min_generation_bits(), 3);
+
+    arena.remove(newest);
+    assert_eq!(arena.min_generation_bits(), 1);
+}
+
+#[test]
+fn push_and_pop_are_lifo() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..5).map(|i| arena.push(i)).collect();
+
+    assert_eq!(arena.pop(), Some((indices[4], 4)));
+    assert_eq!(arena.pop(), Some((indices[3], 3)));
+    assert!(!arena.contains(indices[4]));
+    assert!(!arena.contains(indices[3]));
+    for (i, idx) in indices.iter().take(3).enumerate() {
+        assert_eq!(arena[*idx], i);
+    }
+
+    // The popped slot is reused with a new generation.
+    let pushed = arena.push(10);
+    assert_eq!(pushed.to_idx(), indices[3].to_idx());
+    assert_ne!(pushed, indices[3]);
+
+    let mut empty = Arena::<u32>::new();
+    assert_eq!(empty.pop(), None);
+}
+
+#[test]
+fn version_advances_only_on_insert_and_remove() {
+    let mut arena = Arena::new();
+    let mut version = arena.version();
+    let mut assert_advanced = |arena: &Arena<u32>| {
+        assert!(arena.version() > version);
+        version = arena.version();
+    };
+
+    let a = arena.insert(1);
+    assert_advanced(&arena);
+    let b = arena.insert(2);
+    assert_advanced(&arena);
+
+    let before = arena.version();
+    assert_eq!(arena.get(a), Some(&1));
+    assert!(arena.contains(b));
+    assert_eq!(arena.iter().count(), 2);
+    *arena.get_mut(b).unwrap() += 1;
+    assert_eq!(arena.version(), before);
+
+    arena.remove(a);
+    assert_advanced(&arena);
+    arena.insert(30);
+    arena.retain(|_, v| *v != 30);
+    assert_advanced(&arena);
+    assert_eq!(arena.drain().count(), 1);
+    assert_advanced(&arena);
+    arena.insert(4);
+    arena.clear();
+    assert_advanced(&arena);
+}
+
+#[test]
+fn total_removals_counts_every_removal() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..10).map(|i| arena.insert(i)).collect();
+    for idx in &indices {
+        assert!(arena.remove(*idx).is_some());
+        assert!(arena.remove(*idx).is_none());
+    }
+    assert_eq!(arena.total_removals(), 10);
+
+    for i in 0..6 {
+        arena.insert(i);
+    }
+    arena.retain(|_, v| v % 2 == 0);
+    assert_eq!(arena.total_removals(), 13);
+    assert_eq!(arena.drain().count(), 3);
+    assert_eq!(arena.total_removals(), 16);
+    arena.insert(0);
+    arena.clear();
+    assert_eq!(arena.total_removals(), 17);
+    assert_eq!(arena.clone().total_removals(), 17);
+}
+
+#[test]
+fn runs_split_at_free_slots() {
+    let mut arena = Arena::with_capacity(10);
+    let indices: Vec<_> = (0..10).map(|i| arena.insert(i)).collect();
+    for &slot in &[0, 3, 4, 8] {
+        arena.remove(indices[slot]);
+    }
+
+    let runs: Vec<_> = arena
+        .runs()
+        .map(|(slots, elements)| {
+            let values: Vec<_> = elements.iter().map(|(_, v)| **v).collect();
+            (slots, values)
+        })
+        .collect();
+    assert_eq!(
+        runs,
+        vec![(1..3, vec![1, 2]), (5..8, vec![5, 6, 7]), (9..10, vec![9])]
+    );
+    assert_eq!(Arena::<u32>::new().runs().count(), 0);
+}
+
+#[test]
+fn retain_and_trim_releases_tail() {
+    let mut arena = Arena::with_capacity(100);
+    let indices: Vec<_> = (0..100).map(|i| arena.insert(i)).collect();
+
+    arena.retain_and_trim(|_, v| *v < 50);
+    assert_eq!(arena.len(), 50);
+    assert_eq!(arena.capacity(), 50);
+    for (i, idx) in indices.iter().enumerate() {
+        if i < 50 {
+            assert_eq!(arena[*idx], i);
+        } else {
+            assert!(!arena.contains(*idx));
+        }
+    }
+
+    // Freed slots below the highest survivor are kept.
+    arena.retain_and_trim(|_, v| v % 2 == 1);
+    assert_eq!(arena.capacity(), 50);
+    assert_eq!(arena.insert(0).to_idx(), 48);
+}
+
+#[test]
+fn get2_mut_checked_reports_each_failure() {
+    let mut arena = Arena::with_capacity(4);
+    let a = arena.insert(1);
+    let b = arena.insert(2);
+    let stale = arena.insert(3);
+    arena.remove(stale);
+    let (_, generation) = a.to_raw();
+    let out_of_bounds = Index::from_raw(100, generation);
+
+    {
+        let (x, y) = arena.get2_mut_checked(a, b);
+        *x.unwrap() += 10;
+        *y.unwrap() += 20;
+    }
+    assert_eq!((arena[a], arena[b]), (11, 22));
+
+    {
+        let (x, y) = arena.get2_mut_checked(b, a);
+        assert_eq!((x, y), (Ok(&mut 22), Ok(&mut 11)));
+    }
+
+    let cases = [
+        (a, stale, Ok(11), Err(LookupError::Stale)),
+        (stale, a, Err(LookupError::Stale), Ok(11)),
+        (a, out_of_bounds, Ok(11), Err(LookupError::OutOfBounds)),
+        (out_of_bounds, a, Err(LookupError::OutOfBounds), Ok(11)),
+        (
+            stale,
+            stale,
+            Err(LookupError::Stale),
+            Err(LookupError::Stale),
+        ),
+        (
+            out_of_bounds,
+            out_of_bounds,
+            Err(LookupError::OutOfBounds),
+            Err(LookupError::OutOfBounds),
+        ),
+        (
+            stale,
+            out_of_bounds,
+            Err(LookupError::Stale),
+            Err(LookupError::OutOfBounds),
+        ),
+        (
+            out_of_bounds,
+            stale,
+            Err(LookupError::OutOfBounds),
+            Err(LookupError::Stale),
+        ),
+    ];
+    for (i1, i2, r1, r2) in cases.iter().cloned() {
+        let (x, y) = arena.get2_mut_checked(i1, i2);
+        assert_eq!((x.map(|v| *v), y.map(|v| *v)), (r1, r2));
+    }
+
+    // A newer element in the slot of a stale index resolves only for its own
+    // index.
+    let reused = arena.insert(4);
+    let (x, y) = arena.get2_mut_checked(stale, reused);
+    assert_eq!((x, y), (Err(LookupError::Stale), Ok(&mut 4)));
+}
+
+#[test]
+fn get_disjoint_mut_from_updates_keyed_batch() {
+    let mut arena = Arena::new();
+    let mut ids = HashMap::new();
+    for i in 0..20 {
+        ids.insert(format!("id{}", i), arena.insert(i));
+    }
+    let stale = arena.insert(100);
+    arena.remove(stale);
+
+    let batch: HashMap<String, Index<usize>> = ids
+        .iter()
+        .filter(|(_, idx)| idx.to_idx() < 7)
+        .map(|(key, idx)| (key.clone(), *idx))
+        .collect();
+    let refs = arena.get_disjoint_mut_from(batch.clone()).unwrap();
+    assert_eq!(refs.len(), 7);
+    for (key, value) in refs {
+        assert_eq!(key, format!("id{}", value));
+        *value += 1000;
+    }
+    for (key, idx) in &ids {
+        let i: usize = key[2..].parse().unwrap();
+        let expected = if batch.contains_key(key) { i + 1000 } else { i };
+        assert_eq!(arena[*idx], expected);
+    }
+
+    // Results follow the order of the input.
+    let order = vec![(0, ids["id5"]), (1, ids["id2"]), (2, ids["id9"])];
+    let keys: Vec<_> = arena
+        .get_disjoint_mut_from(order)
+        .unwrap()
+        .into_iter()
+        .map(|(k, _)| k)
+        .collect();
+    assert_eq!(keys, vec![0, 1, 2]);
+
+    let with_stale = vec![(0, ids["id1"]), (1, stale)];
+    assert_eq!(
+        arena.get_disjoint_mut_from(with_stale).unwrap_err(),
+        DisjointError::Lookup(LookupError::Stale)
+    );
+    let overlapping = vec![(0, ids["id1"]), (1, ids["id4"]), (2, ids["id1"])];
+    assert_eq!(
+        arena.get_disjoint_mut_from(overlapping).unwrap_err(),
+        DisjointError::Overlapping
+    );
+    assert!(arena
+        .get_disjoint_mut_from(Vec::<((), _)>::new())
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+#[should_panic(expected = "two identical indices")]
+fn get2_mut_checked_panics_on_identical_indices() {
+    let mut arena = Arena::new();
+    let a = arena.insert(1);
+    let _ = arena.get2_mut_checked(a, a);
+}
+
+#[test]
+fn rotate_generations_invalidates_all_handles() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..10).map(|i| arena.insert(i)).collect();
+    arena.remove(indices[3]);
+    let version = arena.version();
+
+    let remap = arena.rotate_generations();
+    assert_eq!(remap.len(), 9);
+    assert_eq!(arena.len(), 9);
+    assert_ne!(arena.version(), version);
+    for idx in &indices {
+        assert!(!arena.contains(*idx));
+    }
+    for (old, new) in &remap {
+        assert_eq!(old.to_idx(), new.to_idx());
+        assert_eq!(arena[*new], old.to_idx());
+    }
+
+    // New insertions do not collide with either generation of handles.
+    let fresh = arena.insert(100);
+    assert!(remap
+        .iter()
+        .all(|(old, new)| *old != fresh && *new != fresh));
+    assert_eq!(arena[fresh], 100);
+}
+
+#[test]
+fn map_in_place_keeps_handles_and_free_slots() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..10).map(|i| arena.insert(i)).collect();
+    arena.remove(indices[4]);
+    let before: Vec<_> = arena.debug_entries().collect();
+
+    arena.map_in_place(|v| v * v);
+
+    assert!(arena.debug_entries().eq(before));
+    for (i, idx) in indices.iter().enumerate() {
+        if i == 4 {
+            assert!(!arena.contains(*idx));
+        } else {
+            assert_eq!(arena[*idx], i * i);
+        }
+    }
+    assert_eq!(arena.insert(100).to_idx(), 4);
+}
+
+#[test]
+fn try_get_disjoint_mut_is_all_or_nothing() {
+    let mut arena = Arena::new();
+    let indices: Vec<_> = (0..10).map(|i| arena.insert(i)).collect();
+
+    {
+        let [a, b, c] = arena
+            .try_get_disjoint_mut([indices[7], indices[2], indices[5]])
+            .unwrap();
+        assert_eq!((*a, *b, *c), (7, 2, 5));
+        *a += 100;
+        *b += 100;
+        *c += 100;
+    }
+    assert_eq!(arena[indices[7]], 107);
+    assert_eq!(arena[indices[2]], 102);
+    assert_eq!(arena[indices[5]], 105);
+    assert_eq!(arena.try_get_disjoint_mut([]), Some([]));
+
+    arena.remove(indices[3]);
+    assert!(arena
+        .try_get_disjoint_mut([indices[1], indices[3], indices[4]])
+        .is_none());
+    let reused = arena.insert(30);
+    assert_eq!(reused.to_idx(), 3);
+    assert!(arena.try_get_disjoint_mut([indices[3], reused]).is_none());
+
+    assert!(arena
+        .try_get_disjoint_mut([indices[1], indices[4], indices[1]])
+        .is_none());
+    assert_eq!(arena[indices[1]], 1);
+    assert_eq!(arena[indices[4]], 4);
+}
+
+#[test]
+fn stamp_changes_on_every_mutation_and_not_on_reads() {
+    let mut arena = Arena::with_capacity(4);
+    let mut stamp = arena.stamp();
+    let mut check_changed = |arena: &Arena<usize>| {
+        let next = arena.stamp();
+        assert_ne!(next, stamp);
+        stamp = next;
+    };
+
+    let a = arena.insert(1);
+    check_changed(&arena);
+    let b = arena.insert(2);
+    check_changed(&arena);
+    arena.remove(a);
+    check_changed(&arena);
+    arena.reserve(10);
+    check_changed(&arena);
+    arena.retain(|_, v| *v != 2);
+    check_changed(&arena);
+    arena.insert(3);
+    check_changed(&arena);
+    arena.rotate_generations();
+    check_changed(&arena);
+    arena.clear();
+    check_changed(&arena);
+
+    let c = arena.insert(4);
+    let before = arena.stamp();
+    assert_eq!(arena.get(c), Some(&4));
+    assert!(!arena.contains(b));
+    assert_eq!(arena.iter().count(), 1);
+    let _snapshot = arena.clone();
+    assert_eq!(arena.stamp(), before);
+    assert_eq!(arena.stamp().len, 1);
+    assert_eq!(arena.stamp().capacity, arena.capacity());
+}
+
+#[test]
+fn indices_where_collects_a_worklist() {
+    let mut arena = Arena::with_capacity(20);
+    let indices: Vec<_> = (0..20).map(|i| arena.insert(i)).collect();
+    arena.remove(indices[4]);
+    arena.remove(indices[7]);
+
+    let evens = arena.indices_where(|v| v % 2 == 0);
+    assert_eq!(evens.len(), 9);
+    assert!(!evens.contains(&indices[4]));
+    for idx in &evens {
+        arena[*idx] += 100;
+    }
+    for idx in evens.iter().take(3) {
+        arena.remove(*idx);
+    }
+
+    for (i, idx) in indices.iter().enumerate() {
+        let expected = match i {
+            0 | 2 | 4 | 6 | 7 => None,
+            _ if i % 2 == 0 => Some(i + 100),
+            _ => Some(i),
+        };
+        assert_eq!(arena.get(*idx).cloned(), expected);
+    }
+    assert!(arena.indices_where(|v| *v > 1000).is_empty());
+}
+
+#[test]
+fn relocate_moves_element_to_free_slot() {
+    let mut arena = Arena::with_capacity(8);
+    let indices: Vec<_> = (0..8).map(|i| arena.insert(i)).collect();
+    arena.remove(indices[0]);
+    arena.remove(indices[3]);
+
+    let hot = indices[6];
+    let moved = arena.relocate(hot, 0).unwrap();
+    assert_eq!(moved.to_idx(), 0);
+    assert_eq!(arena[moved], 6);
+    assert_eq!(arena.get(hot), None);
+    assert!(!arena.contains(indices[0]));
+    assert_eq!(arena.len(), 6);
+
+    assert_eq!(
+        arena.relocate(hot, 3),
+        Err(RelocateError::Lookup(LookupError::Stale))
+    );
+    assert_eq!(
+        arena.relocate(indices[1], 2),
+        Err(RelocateError::TargetNotFree)
+    );
+    assert_eq!(
+        arena.relocate(indices[1], 8),
+        Err(RelocateError::TargetNotFree)
+    );
+    assert_eq!(arena.relocate(moved, 0), Err(RelocateError::TargetNotFree));
+
+    // The vacated slot and the other hole are still reusable, and reusing
+    // them never revives an old index.
+    let refills: BTreeSet<_> = (0..2).map(|i| arena.insert(100 + i).to_idx()).collect();
+    assert_eq!(refills, vec![3, 6].into_iter().collect());
+    assert!(!arena.has_free());
+    assert_eq!(arena.get(hot), None);
+    assert_eq!(arena[moved], 6);
+}
+
+#[test]
+fn relocate_skips_slots_awaiting_deferred_removal() {
+    let mut arena = Arena::with_capacity(4);
+    let indices: Vec<_> = (0..4).map(|i| arena.insert(i)).collect();
+    arena.set_deferred_removal(true);
+    arena.remove(indices[0]);
+    assert_eq!(
+        arena.relocate(indices[2], 0),
+        Err(RelocateError::TargetNotFree)
+    );
+
+    arena.commit_removals();
+    let moved = arena.relocate(indices[2], 0).unwrap();
+    assert_eq!(arena[moved], 2);
+    assert!(!arena.contains(indices[0]));
+    assert!(!arena.contains(indices[2]));
+    assert_eq!(arena.commit_removals(), 1);
+    assert_eq!(arena.insert(9).to_idx(), 2);
+}
+
+#[test]
+fn retain_map_stays_consistent_when_closure_panics() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut arena = Arena::with_capacity(6);
+    let indices: Vec<_> = (0..6).map(|i| arena.insert(i)).collect();
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        arena.retain_map(|_, v| {
+            if v == 3 {
+                panic!("migration failed");
+            }
+            Some(v + 10)
+        })
+    }));
+    assert!(result.is_err());
+
+    // Elements before the panic were replaced, the one being migrated was
+    // dropped, and the rest are untouched.
+    assert_eq!(arena.len(), 5);
+    assert_eq!(arena.iter().count(), 5);
+    assert_eq!(arena[indices[2]], 12);
+    assert!(!arena.contains(indices[3]));
+    assert_eq!(arena[indices[4]], 4);
+    assert_eq!(arena.repair_free_list(), 0);
+    assert_eq!(arena.insert(30).to_idx(), 3);
+}
+
+#[test]
+fn map_in_place_stays_consistent_when_closure_panics() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut arena = Arena::with_capacity(6);
+    let indices: Vec<_> = (0..6).map(|i| arena.insert(i)).collect();
+    let version = arena.version();
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        arena.map_in_place(|v| {
+            if v == 3 {
+                panic!("conversion failed");
+            }
+            v + 10
+        })
+    }));
+    assert!(result.is_err());
+
+    // The element being mapped was dropped and its slot freed.
+    assert_eq!(arena.len(), 5);
+    assert_eq!(arena.iter().count(), 5);
+    assert!(arena.version() > version);
+    assert_eq!(arena[indices[2]], 12);
+    assert!(!arena.contains(indices[3]));
+    assert_eq!(arena[indices[4]], 4);
+    assert_eq!(arena.repair_free_list(), 0);
+    assert_eq!(arena.insert(30).to_idx(), 3);
+}
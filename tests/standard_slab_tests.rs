@@ -110,3 +110,21 @@ fn clear() {
     assert_eq!(slab.capacity(), 4);
     assert_eq!(slab.len(), 0);
 }
+
+#[test]
+fn iter_insertion_order_matches_inserts() {
+    let mut slab = Slab::with_capacity(3);
+    let mut inserted = Vec::new();
+    for i in 0..100 {
+        let value = (i * 37) % 101;
+        inserted.push((slab.insert(value), value));
+    }
+    slab.extend(vec![7, 3, 5]);
+    inserted.extend(slab.iter().skip(100).map(|(idx, v)| (idx, *v)));
+
+    let yielded: Vec<_> = slab.iter_insertion_order().map(|(idx, v)| (idx, *v)).collect();
+    assert_eq!(yielded.len(), 103);
+    assert_eq!(yielded, inserted);
+    let tail: Vec<_> = yielded[100..].iter().map(|(_, v)| *v).collect();
+    assert_eq!(tail, vec![7, 3, 5]);
+}
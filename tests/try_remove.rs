@@ -0,0 +1,61 @@
+extern crate generational_arena_im;
+
+use generational_arena_im::{
+    Arena, ArenaIndex, FixedGenerationalIndex, Index, RemovalUnsupported, SmallSlab, StandardArena,
+    StandardSlab, TinyArena,
+};
+
+/// Code generic over the generation type can attempt removals.
+fn insert_and_remove<I, G>(arena: &mut Arena<u32, I, G>) -> Result<Option<u32>, RemovalUnsupported>
+where
+    I: ArenaIndex,
+    G: FixedGenerationalIndex,
+{
+    let idx: Index<u32, I, G> = arena.insert(7);
+    arena.try_remove(idx)
+}
+
+#[test]
+fn try_remove_works_on_generational_arenas() {
+    let mut arena = StandardArena::new();
+    assert_eq!(insert_and_remove(&mut arena), Ok(Some(7)));
+    assert!(arena.is_empty());
+
+    let mut tiny = TinyArena::new();
+    assert_eq!(insert_and_remove(&mut tiny), Ok(Some(7)));
+    // The freed slot is reused with a newer generation.
+    let idx = tiny.insert(1);
+    assert_eq!(idx.to_idx(), 0);
+    assert!(idx.gen() > TinyArena::<u32>::new().insert(1).gen());
+}
+
+#[test]
+fn try_remove_respects_deferred_removal() {
+    let mut arena = StandardArena::with_capacity(2);
+    arena.set_deferred_removal(true);
+    let a = arena.insert(1);
+    arena.insert(2);
+    let generation = arena.generation();
+
+    assert_eq!(arena.try_remove(a), Ok(Some(1)));
+    assert!(!arena.contains(a));
+    assert_eq!(arena.generation(), generation);
+    // The slot stays off the free list until the removal is committed.
+    assert_ne!(arena.insert(3).to_idx(), a.to_idx());
+    assert_eq!(arena.commit_removals(), 1);
+    let reused = arena.insert(4);
+    assert_eq!(reused.to_idx(), a.to_idx());
+    assert!(!arena.contains(a));
+}
+
+#[test]
+fn try_remove_reports_unsupported_on_slabs() {
+    let mut slab = StandardSlab::new();
+    assert_eq!(insert_and_remove(&mut slab), Err(RemovalUnsupported));
+    assert_eq!(slab.len(), 1);
+
+    let mut small = SmallSlab::new();
+    let idx = small.insert(3);
+    assert_eq!(small.remove(idx), Err(RemovalUnsupported));
+    assert_eq!(small[idx], 3);
+}
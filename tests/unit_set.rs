@@ -0,0 +1,38 @@
+extern crate generational_arena_im;
+
+use generational_arena_im::{Index, NonzeroGeneration, StandardArena};
+
+type Key = Index<(), usize, NonzeroGeneration<usize>>;
+
+#[test]
+fn insert_key_handles_are_unique_and_go_stale() {
+    let mut set = StandardArena::<()>::new();
+    let mut live: Vec<Key> = Vec::new();
+    let mut dead: Vec<Key> = Vec::new();
+    let mut seen: Vec<Key> = Vec::new();
+
+    for round in 0..4000 {
+        let key = set.insert_key();
+        assert!(
+            !seen.contains(&key),
+            "handle {:?} was handed out twice",
+            key
+        );
+        seen.push(key);
+        live.push(key);
+        if round % 3 != 0 {
+            let victim = live.swap_remove((round * 7) % live.len());
+            assert_eq!(set.remove(victim), Some(()));
+            dead.push(victim);
+        }
+    }
+
+    assert_eq!(set.len(), live.len());
+    for key in &live {
+        assert!(set.contains(*key));
+    }
+    for key in &dead {
+        assert!(!set.contains(*key));
+        assert_eq!(set.remove(*key), None);
+    }
+}